@@ -0,0 +1,22 @@
+extern crate pathtracer;
+
+use pathtracer::*;
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let positions = [(0, 0), (60, 30), (120, 0), (90, 80), (20, 70)];
+    let nodes = Node::linked_list(Node::from_list(&positions));
+    let map = Map::new()
+        .background(image::Rgba([255, 255, 255, 255]))
+        .map(&nodes);
+
+    // The dashboard looks up which Node was clicked by the hash in the fragment of the link.
+    for (name, node) in ["A", "B", "C", "D", "E"].iter().zip(nodes.iter()) {
+        println!("#{} {}", node.hash(), name);
+    }
+
+    map.save_image_map(Path::new("out.html"), "network")?;
+    map.save_hitboxes(Path::new("out.json"))?;
+    map.save(Path::new("out.png"))
+}
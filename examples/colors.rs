@@ -0,0 +1,33 @@
+extern crate pathtracer;
+
+use pathtracer::{
+    color::{legend::*, Categorical, Scale},
+    *,
+};
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let palette = Categorical::default();
+    let scale = Scale::viridis().domain(0.0, 200.0);
+    let names = ["North", "South"];
+
+    let mut nodes = Vec::new();
+    for (i, y) in [0, 60].iter().enumerate() {
+        for x in (0..=200).step_by(20) {
+            let mut node = Node::new(&format!("{}{}", i, x), coordinate!(x, *y));
+            node.color = palette.get(i);
+            nodes.push(node);
+
+            let mut node = Node::new(&format!("{}{}v", i, x), coordinate!(x, *y + 20));
+            node.color = scale.color(f64::from(x));
+            nodes.push(node);
+        }
+    }
+
+    Map::new()
+        .map(&nodes)
+        .legend(&Legend::from_categorical(&palette, &names).corner(Corner::BottomLeft))
+        .legend(&Legend::from_scale(&scale, 5).corner(Corner::BottomRight))
+        .save(Path::new("out.png"))
+}
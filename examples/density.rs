@@ -0,0 +1,23 @@
+extern crate pathtracer;
+
+use pathtracer::{map::heatmap::Heatmap, *};
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let mut groups = Vec::new();
+    let spread = 80;
+
+    for (i, c) in Shape::Square.area(4).iter().enumerate() {
+        let mut group = cluster!(c.x * spread, c.y * spread);
+        group.radius(2);
+        group.color(tools::seed_rgba((i * 23) as u64));
+        group.add(1000);
+        groups.push(group);
+    }
+
+    let heatmap = Heatmap::new().cell(3).radius(4);
+    Map::new()
+        .map_density(&groups, &heatmap)
+        .save(&Path::new("out.png"))
+}
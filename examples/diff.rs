@@ -0,0 +1,20 @@
+extern crate pathtracer;
+
+use pathtracer::{diff::Diff, map::gif::Gif, *};
+
+fn main() -> std::io::Result<()> {
+    let before = Node::linked_list(Node::from_list(&[(0, 0), (40, 20), (80, 10), (120, 40)]));
+
+    let mut after = before[..3].to_vec();
+    after[1].geo = Coordinate::new(40, 60);
+    let mut e = Node::new("E", Coordinate::new(100, 80));
+    e.link(&after[2]);
+    after.push(e);
+
+    let diff = Diff::new(&before, &after);
+    diff.map().save(std::path::Path::new("out.png"))?;
+
+    let mut gif = Gif::new("out.gif", 140, 100);
+    diff.morph(&mut gif, 20)?;
+    gif.hold(100)
+}
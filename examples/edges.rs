@@ -0,0 +1,43 @@
+extern crate pathtracer;
+
+use pathtracer::*;
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let styles = [
+        EdgeStyle::Direct,
+        EdgeStyle::Quadratic(30),
+        EdgeStyle::Cubic(25),
+        EdgeStyle::CatmullRom(coordinate!(0, 25)),
+        EdgeStyle::Orthogonal,
+        EdgeStyle::Bundled(85),
+    ];
+
+    let mut nodes = Vec::new();
+    for (i, style) in styles.iter().enumerate() {
+        let y = i as i32 * 50;
+        let a = Node::new(&format!("A{}", i), coordinate!(0, y));
+        let mut b = Node::new(&format!("B{}", i), coordinate!(150, y + 30));
+        let obstacle = Node::new(&format!("C{}", i), coordinate!(75, y + 15));
+        b.link(&a);
+        b.hl_mut(0)?.style(*style);
+        b.hl_mut(0)?.arrow(Arrow::new().head(6).clip_to(&a));
+        nodes.extend_from_slice(&[a, obstacle, b]);
+    }
+
+    // Colors every edge by where it starts, and breaks up the routed ones.
+    let palette = color::Categorical::default();
+    Map::new()
+        .map_styled(&nodes, &|hl| {
+            let mut hl = *hl;
+            hl.color(palette.by_hash(hl.f));
+            match hl.style {
+                EdgeStyle::Orthogonal => hl.stroke(Stroke::Dashed(6, 3)),
+                EdgeStyle::Bundled(_) => hl.stroke(Stroke::Dotted(3)),
+                _ => (),
+            }
+            hl
+        })
+        .save(Path::new("out.png"))
+}
@@ -0,0 +1,43 @@
+extern crate pathtracer;
+
+use pathtracer::{geo::*, *};
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let cities = [
+        ("Stockholm", 59.3293, 18.0686),
+        ("Gothenburg", 57.7089, 11.9746),
+        ("Malmö", 55.6050, 13.0038),
+        ("Uppsala", 59.8586, 17.6389),
+        ("Umeå", 63.8258, 20.2630),
+        ("Kiruna", 67.8558, 20.2253),
+    ];
+
+    let viewport = Viewport::new(
+        Projection::Mercator,
+        LatLon::new(55.0, 10.0),
+        LatLon::new(69.0, 25.0),
+        400,
+    );
+
+    let mut nodes = cities
+        .iter()
+        .map(|&(name, lat, lon)| viewport.node(name, LatLon::new(lat, lon)))
+        .collect::<Vec<_>>();
+
+    for n in nodes.iter_mut() {
+        n.color = tools::seed_rgba(n.hash);
+    }
+
+    let nodes = Node::linked_list(nodes);
+    let path = Network::new(nodes.clone())
+        .path_weighted("Stockholm", "Kiruna", &|a, b| viewport.distance(a, b))?;
+
+    for leg in path.windows(2) {
+        println!("{} km", viewport.distance(leg[0].geo, leg[1].geo) / 1000);
+    }
+
+    Map::new_geo(&viewport)
+        .map(&nodes)
+        .save(Path::new("out.png"))
+}
@@ -11,9 +11,9 @@ fn main() -> Result<(), std::io::Error> {
 
     let mut pos = Vec::new();
     let y = vec![5, 30, 45, 35, 40, 80, 75, 70, 25, 30];
-    let spread = 300 / (y.len() - 1) as i16;
+    let spread = 300 / (y.len() - 1) as i32;
     for (i, y) in y.iter().enumerate() {
-        pos.push((i as i16 * spread, -*y));
+        pos.push((i as i32 * spread, -*y));
     }
     let line = Node::linked_list(Node::from_list(&pos));
 
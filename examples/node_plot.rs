@@ -8,21 +8,21 @@ fn main() -> std::io::Result<()> {
     let width = 290;
     let height = 90;
     let radius = 38;
-    let x_max: i16 = (width / radius) as i16;
-    let count: i16 = x_max * (height / radius) as i16;
+    let x_max: i32 = (width / radius) as i32;
+    let count: i32 = x_max * (height / radius) as i32;
     let mut gif = Gif::new("out.gif", width, height + 5);
 
     let f = |i: usize, d: f64| -> Coordinate {
         let i = i as f64;
         Coordinate {
-            x: (d * f64::cos(i)) as i16,
-            y: (d * f64::sin(i)) as i16,
+            x: (d * f64::cos(i)) as i32,
+            y: (d * f64::sin(i)) as i32,
         }
     };
 
     let mut groups = Vec::new();
-    for c in 0i16..count {
-        let rad = radius as i16;
+    for c in 0i32..count {
+        let rad = radius as i32;
         let mut group = cluster!((c % x_max) * rad, (c / x_max) * rad);
         group.radius(radius as u32);
         group.color(tools::seed_rgba(c as u64 * 32));
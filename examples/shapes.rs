@@ -0,0 +1,43 @@
+extern crate pathtracer;
+
+use pathtracer::{color::Categorical, *};
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let shapes = [
+        Shape::Circle,
+        Shape::Square,
+        Shape::Triangle,
+        Shape::Diamond,
+        Shape::Hexagon,
+        Shape::Star,
+        Shape::Ring,
+        Shape::RoundedRect,
+        Shape::Custom(|size| {
+            let size = size as i32;
+            (0..size)
+                .flat_map(|i| vec![Coordinate::new(i, i), Coordinate::new(size - 1 - i, i)])
+                .collect()
+        }),
+    ];
+    let palette = Categorical::default();
+
+    // Filled shapes on the first row, and their outlines on the second.
+    let mut nodes = Vec::new();
+    for (i, shape) in shapes.iter().enumerate() {
+        for (row, border) in [None, Some(2)].iter().enumerate() {
+            let mut node = Node::new(
+                &format!("{}{}", i, row),
+                coordinate!(i as i32 * 30, row as i32 * 30),
+            );
+            node.shape = Some(*shape);
+            node.border = *border;
+            node.radius = Some(12);
+            node.color = palette.get(i);
+            nodes.push(node);
+        }
+    }
+
+    Map::new().map(&nodes).save(Path::new("out.png"))
+}
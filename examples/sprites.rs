@@ -0,0 +1,41 @@
+extern crate pathtracer;
+
+use pathtracer::{color::Categorical, *};
+
+use std::path::Path;
+
+/**
+Draws a round avatar, a stand-in for an image loaded from disk.
+ */
+fn avatar(color: image::Rgba<u8>) -> image::RgbaImage {
+    image::RgbaImage::from_fn(16, 16, |x, y| {
+        let (dx, dy) = (x as i32 - 8, y as i32 - 8);
+        if dx * dx + dy * dy < 64 {
+            color
+        } else {
+            image::Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+fn main() -> std::io::Result<()> {
+    let background = image::RgbaImage::from_fn(200, 120, |x, y| {
+        image::Rgba([230 - (x / 10) as u8, 235 - (y / 10) as u8, 220, 255])
+    });
+    let authors = ["alice", "bob", "carol"];
+    let palette = Categorical::default();
+
+    let mut map = Map::new_background(background);
+    for (i, author) in authors.iter().enumerate() {
+        map = map.sprite(author, avatar(palette.get(i)));
+    }
+
+    let positions = [(20, 20), (60, 90), (110, 40), (150, 80), (180, 20)];
+    let mut nodes = Node::linked_list(Node::from_list(&positions));
+    for (i, node) in nodes.iter_mut().enumerate() {
+        let scale = if i == 0 { 1.5 } else { 1.0 };
+        node.sprite = Some(Sprite::new(authors[i % authors.len()]).scale(scale));
+    }
+
+    map.map(&nodes).save(Path::new("out.png"))
+}
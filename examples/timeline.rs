@@ -0,0 +1,35 @@
+extern crate image;
+extern crate pathtracer;
+
+use pathtracer::{
+    map::{gif::*, timeline::*},
+    *,
+};
+
+fn main() -> std::io::Result<()> {
+    let mut gif = Gif::new("out.gif", 150, 120);
+    let mut timeline = Timeline::new();
+
+    let drops = Node::from_list(&[(20, 0), (50, 0), (80, 0), (110, 0)]);
+    for (i, mut node) in drops.into_iter().enumerate() {
+        node.color = image::Rgba([255, 50, 50, 255]);
+        let track = Track::new(node).key(Keyframe::new(0.2 * i as f64)).key(
+            Keyframe::new(0.2 * i as f64 + 1.0)
+                .geo(coordinate!(node.geo.x, 100))
+                .easing(Easing::Bounce),
+        );
+        timeline.parallel(track);
+    }
+
+    let grow = Track::new(node!(65, 50))
+        .key(
+            Keyframe::new(0.5)
+                .radius(20)
+                .color(image::Rgba([0, 100, 0, 255])),
+        )
+        .key(Keyframe::new(1.0).radius(4).easing(Easing::EaseInOut));
+    timeline.sequential(grow);
+
+    timeline.render(&mut gif, 25)?;
+    gif.hold(100)
+}
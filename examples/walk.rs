@@ -0,0 +1,21 @@
+extern crate pathtracer;
+
+use pathtracer::{
+    map::{gif::Gif, walk::Walk},
+    *,
+};
+
+fn main() -> std::io::Result<()> {
+    let pos = [(0, 0), (60, 20), (120, 10), (150, 70), (90, 110), (20, 90)];
+    let mut nodes = Node::linked_list(Node::from_list(&pos));
+    nodes[2].hl_mut(0)?.style(EdgeStyle::Straight);
+
+    let path = Network::new(nodes.clone()).path("A", "F")?;
+    let mut walk = Walk::new(&path)?;
+    walk.speed(6);
+
+    let mut gif = Gif::new("out.gif", 160, 120);
+    gif.delay(4);
+    walk.render(&mut gif, &nodes)?;
+    gif.hold(200)
+}
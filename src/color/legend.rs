@@ -0,0 +1,372 @@
+/*!
+Legends explaining what the colors of a Map mean.
+
+Labels are drawn with a small built in pixel font, which has uppercase letters, digits and some punctuation.
+Lowercase letters are drawn as uppercase.
+ */
+
+use super::{super::*, Categorical, Scale};
+use image::Rgba;
+
+/**
+The corner of the image a Legend is placed in.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/**
+A block of colored swatches, each followed by a label.
+
+
+## Examples
+
+```
+# use pathtracer::{color::{legend::*, Categorical}, *};
+let palette = Categorical::default();
+let legend = Legend::from_categorical(&palette, &["Roads", "Rivers"]).corner(Corner::BottomLeft);
+
+let mut nodes = Node::from_list(&[(0, 0), (100, 60)]);
+nodes[1].color = palette.get(1);
+let image = Map::new().map(&nodes).legend(&legend).consume();
+```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legend {
+    entries: Vec<(String, Rgba<u8>)>,
+    corner: Corner,
+    background: Rgba<u8>,
+    scale: u32,
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Legend {
+            entries: Vec::new(),
+            corner: Corner::default(),
+            background: Rgba([255, 255, 255, 220]),
+            scale: 1,
+        }
+    }
+}
+
+impl Legend {
+    /**
+    Constructs an empty Legend placed in the top right corner.
+     */
+    pub fn new() -> Self {
+        Legend::default()
+    }
+
+    /**
+    Constructs a Legend with one entry for each label, colored by the palette in order.
+     */
+    pub fn from_categorical(palette: &Categorical, labels: &[&str]) -> Self {
+        labels
+            .iter()
+            .enumerate()
+            .fold(Legend::new(), |legend, (i, label)| {
+                legend.entry(label, palette.get(i))
+            })
+    }
+
+    /**
+    Constructs a Legend with evenly spaced values across the domain of the scale, from the lowest value.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::color::{legend::Legend, Scale};
+    let legend = Legend::from_scale(&Scale::viridis().domain(0.0, 100.0), 5);
+    assert_eq!(legend.labels(), vec!["0", "25", "50", "75", "100"]);
+    ```
+     */
+    pub fn from_scale(scale: &Scale, steps: usize) -> Self {
+        let last = std::cmp::max(steps, 2) - 1;
+        (0..=last).fold(Legend::new(), |legend, i| {
+            let value = scale.min + (scale.max - scale.min) * i as f64 / last as f64;
+            let label = format!("{}", (value * 100.0).round() / 100.0);
+            legend.entry(&label, scale.color(value))
+        })
+    }
+
+    /**
+    Adds an entry to the bottom of the Legend.
+     */
+    pub fn entry(mut self, label: &str, color: Rgba<u8>) -> Self {
+        self.entries.push((label.to_string(), color));
+        self
+    }
+
+    /**
+    Sets the corner of the image the Legend is placed in.
+     */
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /**
+    Sets the color behind the entries.
+     */
+    pub fn background(mut self, color: Rgba<u8>) -> Self {
+        self.background = color;
+        self
+    }
+
+    /**
+    Sets how many pixels wide each pixel of the font is, which enlarges the entire Legend.
+     */
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.scale = std::cmp::max(scale, 1);
+        self
+    }
+
+    /**
+    Returns the labels of every entry, in order.
+     */
+    pub fn labels(&self) -> Vec<&str> {
+        self.entries.iter().map(|(l, _)| l.as_str()).collect()
+    }
+
+    /**
+    Returns the width and height of the Legend in pixels.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{color::legend::Legend, *};
+    let legend = Legend::new().entry("AB", image::Rgba([0, 0, 0, 255]));
+    assert_eq!(legend.dimensions(), Coordinate::new(18, 9));
+    ```
+     */
+    pub fn dimensions(&self) -> Coordinate {
+        if self.entries.is_empty() {
+            return coordinate!();
+        }
+        let s = self.scale as i32;
+        let chars = self
+            .entries
+            .iter()
+            .map(|(l, _)| l.chars().count() as i32)
+            .max()
+            .unwrap_or(0);
+        let rows = self.entries.len() as i32;
+        let label = std::cmp::max(chars * (GLYPH_WIDTH + 1) - 1, 0);
+        coordinate!(
+            s * (PADDING * 2 + GLYPH_HEIGHT + GAP + label),
+            s * (PADDING * 2 + rows * GLYPH_HEIGHT + (rows - 1) * GAP)
+        )
+    }
+
+    /**
+    Draws the Legend in its corner of the image.
+
+    Parts of the Legend that do not fit on the image are left out.
+    The background is blended with what is drawn beneath it.
+     */
+    pub fn draw(&self, image: &mut IW) {
+        let size = self.dimensions();
+        if size == coordinate!() {
+            return;
+        }
+
+        let dim = image.dimensions();
+        let origin = match self.corner {
+            Corner::TopLeft => coordinate!(),
+            Corner::TopRight => coordinate!(dim.x - size.x, 0),
+            Corner::BottomLeft => coordinate!(0, dim.y - size.y),
+            Corner::BottomRight => dim - size,
+        };
+        let s = self.scale as i32;
+        let mut paint = |c: Coordinate, color: Rgba<u8>, blend: bool| {
+            let c = origin + c;
+            if c.x >= 0 && c.y >= 0 && c.x < dim.x && c.y < dim.y {
+                if blend {
+                    image.blend(c.x as u32, c.y as u32, color);
+                } else {
+                    image.put(&c, color);
+                }
+            }
+        };
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                paint(coordinate!(x, y), self.background, true);
+            }
+        }
+
+        for (row, (label, color)) in self.entries.iter().enumerate() {
+            let top = s * (PADDING + row as i32 * (GLYPH_HEIGHT + GAP));
+            for y in 0..s * GLYPH_HEIGHT {
+                for x in 0..s * GLYPH_HEIGHT {
+                    paint(coordinate!(s * PADDING + x, top + y), *color, false);
+                }
+            }
+
+            let left = s * (PADDING + GLYPH_HEIGHT + GAP);
+            for (i, c) in label.chars().enumerate() {
+                let glyph = glyph(c);
+                for (gy, bits) in glyph.iter().enumerate() {
+                    for gx in 0..GLYPH_WIDTH {
+                        if bits & (1 << (GLYPH_WIDTH - 1 - gx)) == 0 {
+                            continue;
+                        }
+                        let x = left + s * (i as i32 * (GLYPH_WIDTH + 1) + gx);
+                        let y = top + s * gy as i32;
+                        for py in 0..s {
+                            for px in 0..s {
+                                paint(coordinate!(x + px, y + py), TEXT, false);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const GLYPH_WIDTH: i32 = 3;
+const GLYPH_HEIGHT: i32 = 5;
+const PADDING: i32 = 2;
+const GAP: i32 = 2;
+const TEXT: Rgba<u8> = Rgba {
+    data: [0, 0, 0, 255],
+};
+
+/**
+Returns the rows of a character in the pixel font, where the highest of the three bits is the leftmost pixel.
+
+Unknown characters are drawn as a question mark.
+ */
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ' ' => [0; 5],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas(w: u32, h: u32) -> IW {
+        IW::from(map::gen_canvas(w, h))
+    }
+
+    #[test]
+    fn test_empty_legend() {
+        let mut image = canvas(10, 10);
+        Legend::new().draw(&mut image);
+        assert!(image.image().pixels().all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_corners() {
+        let red = Rgba([255, 0, 0, 255]);
+        let legend = Legend::new().entry("", red).background(red);
+        let size = legend.dimensions();
+        assert_eq!(size, coordinate!(11, 9));
+
+        let mut image = canvas(20, 20);
+        legend.clone().corner(Corner::BottomRight).draw(&mut image);
+        assert_eq!(image.image().get_pixel(19, 19), &red);
+        assert_eq!(image.image().get_pixel(0, 0)[3], 0);
+
+        let mut image = canvas(20, 20);
+        legend.corner(Corner::TopLeft).draw(&mut image);
+        assert_eq!(image.image().get_pixel(0, 0), &red);
+        assert_eq!(image.image().get_pixel(19, 19)[3], 0);
+    }
+
+    #[test]
+    fn test_swatch_and_text() {
+        let blue = Rgba([0, 0, 255, 255]);
+        let legend = Legend::new().entry("1", blue).corner(Corner::TopLeft);
+        let mut image = canvas(30, 30);
+        legend.draw(&mut image);
+        assert_eq!(image.image().get_pixel(2, 2), &blue);
+        // The top of the digit one is its middle pixel.
+        assert_eq!(image.image().get_pixel(10, 2), &TEXT);
+        assert_eq!(image.image().get_pixel(9, 2), &legend.background);
+    }
+
+    #[test]
+    fn test_background_blends() {
+        let white = Rgba([255, 255, 255, 255]);
+        let legend = Legend::new()
+            .entry("1", Rgba([0, 0, 255, 255]))
+            .background(Rgba([0, 0, 0, 128]));
+        let mut image = IW::from(image::RgbaImage::from_pixel(30, 30, white));
+        legend.corner(Corner::TopLeft).draw(&mut image);
+        let pixel = image.image().get_pixel(0, 0);
+        assert!(pixel[0] > 0 && pixel[0] < 255);
+    }
+
+    #[test]
+    fn test_larger_than_image() {
+        let legend = Legend::new()
+            .entry("A LONG LABEL", Rgba([0, 0, 0, 255]))
+            .scale(3);
+        legend.draw(&mut canvas(5, 5));
+    }
+
+    #[test]
+    fn test_lowercase() {
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_ne!(glyph('a'), glyph('?'));
+    }
+}
@@ -0,0 +1,308 @@
+/*!
+Palettes and scales for coloring elements by category or by value.
+
+The defaults are chosen to stay distinguishable with the most common forms of color blindness.
+ */
+
+use image::Rgba;
+
+pub mod legend;
+
+/**
+A list of distinct colors, used to color elements by which category they belong to.
+
+Defaults to the Okabe-Ito palette, which is safe for color blindness.
+
+
+## Examples
+
+```
+# use pathtracer::color::Categorical;
+let palette = Categorical::default();
+assert_eq!(palette.get(0), palette.get(palette.len()));
+assert_ne!(palette.get(0), palette.get(1));
+```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Categorical {
+    colors: Vec<Rgba<u8>>,
+}
+
+impl Default for Categorical {
+    fn default() -> Self {
+        Categorical::okabe_ito()
+    }
+}
+
+impl Categorical {
+    /**
+    Constructs a palette from a list of colors.
+
+
+    ## Panics
+
+    If the list of colors is empty.
+     */
+    pub fn new(colors: &[Rgba<u8>]) -> Self {
+        assert!(!colors.is_empty(), "a palette requires at least one color");
+        Categorical {
+            colors: colors.to_vec(),
+        }
+    }
+
+    /**
+    The eight colors of the Okabe-Ito palette, designed to be told apart with color blindness.
+     */
+    pub fn okabe_ito() -> Self {
+        Categorical::new(&hex(&[
+            0xE6_9F_00, 0x56_B4_E9, 0x00_9E_73, 0xF0_E4_42, 0x00_72_B2, 0xD5_5E_00, 0xCC_79_A7,
+            0x00_00_00,
+        ]))
+    }
+
+    /**
+    The ten colors of the Tableau palette.
+     */
+    pub fn tableau() -> Self {
+        Categorical::new(&hex(&[
+            0x4E_79_A7, 0xF2_8E_2B, 0xE1_57_59, 0x76_B7_B2, 0x59_A1_4F, 0xED_C9_48, 0xB0_7A_A1,
+            0xFF_9D_A7, 0x9C_75_5F, 0xBA_B0_AC,
+        ]))
+    }
+
+    /**
+    Returns the color of a category, starting over from the first color when the palette runs out.
+     */
+    pub fn get(&self, index: usize) -> Rgba<u8> {
+        self.colors[index % self.colors.len()]
+    }
+
+    /**
+    Returns the color of a hash, so that equal hashes are always given the same color.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{color::Categorical, *};
+    let palette = Categorical::default();
+    let mut node = Node::new("A", coordinate!());
+    node.color = palette.by_hash(node.hash);
+    ```
+     */
+    pub fn by_hash(&self, hash: u64) -> Rgba<u8> {
+        self.colors[(hash % self.colors.len() as u64) as usize]
+    }
+
+    /**
+    Returns all colors in the palette.
+     */
+    pub fn colors(&self) -> &[Rgba<u8>] {
+        &self.colors
+    }
+
+    /**
+    Returns the number of colors in the palette.
+     */
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /**
+    Returns true if the palette has no colors, which can not happen for palettes constructed with new.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+}
+
+/**
+Maps numeric values to colors, by interpolating between evenly spaced color stops.
+
+Values are mapped from the domain, which is 0 to 1 unless set. Values outside of it are clamped.
+Defaults to the viridis scale, which is sequential and safe for color blindness.
+
+
+## Examples
+
+```
+# use pathtracer::color::Scale;
+# use image::Rgba;
+let scale = Scale::sequential(Rgba([0, 0, 0, 255]), Rgba([200, 100, 0, 255])).domain(0.0, 10.0);
+assert_eq!(scale.color(5.0), Rgba([100, 50, 0, 255]));
+assert_eq!(scale.color(20.0), Rgba([200, 100, 0, 255]));
+```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+    stops: Vec<Rgba<u8>>,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Scale::viridis()
+    }
+}
+
+impl Scale {
+    /**
+    Constructs a scale that passes through every color stop, in order.
+
+
+    ## Panics
+
+    If the list of color stops is empty.
+     */
+    pub fn new(stops: &[Rgba<u8>]) -> Self {
+        assert!(!stops.is_empty(), "a scale requires at least one color");
+        Scale {
+            stops: stops.to_vec(),
+            min: 0.0,
+            max: 1.0,
+        }
+    }
+
+    /**
+    Constructs a scale from a low to a high color.
+     */
+    pub fn sequential(low: Rgba<u8>, high: Rgba<u8>) -> Self {
+        Scale::new(&[low, high])
+    }
+
+    /**
+    Constructs a scale from a low color, through the middle color, to a high color.
+
+    The middle color is placed at the center of the domain.
+     */
+    pub fn diverging(low: Rgba<u8>, mid: Rgba<u8>, high: Rgba<u8>) -> Self {
+        Scale::new(&[low, mid, high])
+    }
+
+    /**
+    The sequential viridis scale, from dark purple to yellow.
+     */
+    pub fn viridis() -> Self {
+        Scale::new(&hex(&[
+            0x44_01_54, 0x3B_52_8B, 0x21_91_8C, 0x5E_C9_62, 0xFD_E7_25,
+        ]))
+    }
+
+    /**
+    A diverging scale from blue, through light grey, to orange, which is safe for color blindness.
+     */
+    pub fn blue_orange() -> Self {
+        Scale::new(&hex(&[0x21_66_AC, 0xF7_F7_F7, 0xE0_82_14]))
+    }
+
+    /**
+    Sets the values mapped to the first and the last color stop.
+     */
+    pub fn domain(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /**
+    Returns the color of the value.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::color::Scale;
+    # use image::Rgba;
+    let white = Rgba([255, 255, 255, 255]);
+    let scale = Scale::diverging(Rgba([0, 0, 255, 255]), white, Rgba([255, 0, 0, 255]))
+        .domain(-1.0, 1.0);
+    assert_eq!(scale.color(0.0), white);
+    ```
+     */
+    pub fn color(&self, value: f64) -> Rgba<u8> {
+        let range = self.max - self.min;
+        let t = if range == 0.0 {
+            0.0
+        } else {
+            ((value - self.min) / range).clamp(0.0, 1.0)
+        };
+
+        let last = self.stops.len() - 1;
+        let position = t * last as f64;
+        let i = std::cmp::min(position.floor() as usize, last);
+        if i == last {
+            return self.stops[last];
+        }
+
+        let (a, b) = (self.stops[i], self.stops[i + 1]);
+        let t = position - i as f64;
+        let mut color = a;
+        for c in 0..4 {
+            color[c] = (f64::from(a[c]) + (f64::from(b[c]) - f64::from(a[c])) * t).round() as u8;
+        }
+        color
+    }
+}
+
+/**
+Converts 0xRRGGBB values to opaque colors.
+ */
+fn hex(values: &[u32]) -> Vec<Rgba<u8>> {
+    values
+        .iter()
+        .map(|v| Rgba([(v >> 16) as u8, (v >> 8) as u8, *v as u8, 255]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex(&[0x12_34_56]), vec![Rgba([0x12, 0x34, 0x56, 255])]);
+    }
+
+    #[test]
+    fn test_palettes_are_distinct() {
+        for palette in [Categorical::okabe_ito(), Categorical::tableau()].iter() {
+            let mut colors = palette.colors().to_vec();
+            colors.dedup();
+            assert_eq!(colors.len(), palette.len());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_palette() {
+        Categorical::new(&[]);
+    }
+
+    #[test]
+    fn test_scale_ends() {
+        let scale = Scale::viridis().domain(10.0, 20.0);
+        assert_eq!(scale.color(10.0), scale.stops[0]);
+        assert_eq!(scale.color(20.0), scale.stops[4]);
+        assert_eq!(scale.color(-5.0), scale.stops[0]);
+    }
+
+    #[test]
+    fn test_scale_passes_stops() {
+        let scale = Scale::viridis();
+        assert_eq!(scale.color(0.5), scale.stops[2]);
+        assert_eq!(scale.color(0.25), scale.stops[1]);
+    }
+
+    #[test]
+    fn test_scale_empty_domain() {
+        let scale = Scale::blue_orange().domain(3.0, 3.0);
+        assert_eq!(scale.color(3.0), scale.stops[0]);
+    }
+
+    #[test]
+    fn test_scale_single_stop() {
+        let color = Rgba([1, 2, 3, 4]);
+        assert_eq!(Scale::new(&[color]).color(0.7), color);
+    }
+}
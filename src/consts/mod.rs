@@ -6,9 +6,14 @@ These constants are used to set default values for certain properties.
 
 pub const MAX_LINKS: usize = 5;
 pub const NETWORK_REM: usize = 666;
+pub const QUAD_CAPACITY: usize = 8;
 pub const DEFAULT_SIZE: u16 = 4;
 pub const DEFAULT_SHADE: u16 = 20;
 pub const DEFAULT_LINK_SIZE: u16 = 2;
+pub const DEFAULT_ARROW_SIZE: u16 = 6;
+pub const DEFAULT_GIF_DELAY: u16 = 20;
+pub const TILE_SIZE: u32 = 256;
+pub const ROUTE_MARGIN: i32 = 32;
 pub const DEFAULT_RGBA: image::Rgba<u8> = image::Rgba {
     data: [0, 0, 0, 255],
 };
@@ -6,7 +6,7 @@ extern crate pythagoras;
 extern crate rand;
 
 use super::{tools::roll, Coordinate};
-use std::{cmp::Ordering, f64};
+use std::{cmp::Ordering, convert::TryFrom, f64};
 
 /**
 Constructs a vector of generic structs from a given list convered to Coordinates.
@@ -16,7 +16,7 @@ Constructs a vector of generic structs from a given list convered to Coordinates
 examples/city.rs
 
 */
-pub fn from_list<T>(list: &[(i16, i16)], get: &Fn(Coordinate, usize) -> T) -> Vec<T> {
+pub fn from_list<T>(list: &[(i32, i32)], get: &Fn(Coordinate, usize) -> T) -> Vec<T> {
     let mut result: Vec<T> = Vec::new();
     for (i, &(x, y)) in list.iter().enumerate() {
         result.push(get(Coordinate::new(x, y), i));
@@ -29,8 +29,8 @@ Constructs a randomly positioned coordinate.
 */
 pub fn gen() -> Coordinate {
     Coordinate {
-        x: rand::random::<i16>(),
-        y: rand::random::<i16>(),
+        x: i32::from(rand::random::<i16>()),
+        y: i32::from(rand::random::<i16>()),
     }
 }
 
@@ -43,7 +43,7 @@ Calculate a coordinate based on another coordinate and a function.
 ```
 # use pathtracer::{coordinate::*, Coordinate};
 let c1 = Coordinate::new(0, 0);
-let f = |i: usize| -> Coordinate { Coordinate::new(i as i16, i as i16) };
+let f = |i: usize| -> Coordinate { Coordinate::new(i as i32, i as i32) };
 let c2 = calc(c1, 5, &f);
 assert_eq!(c2, Coordinate { x: 5, y: 5 });
 ```
@@ -72,7 +72,7 @@ assert_eq!(difference, (100, 100));
 # }
 ```
 */
-pub fn diff(c1: Coordinate, c2: Coordinate) -> (i16, i16) {
+pub fn diff(c1: Coordinate, c2: Coordinate) -> (i32, i32) {
     let c = (c1 - c2).abs();
     (c.x, c.y)
 }
@@ -93,8 +93,7 @@ assert_eq!(distance, 5);
 ```
 */
 pub fn distance(a: Coordinate, b: Coordinate) -> u32 {
-    let diff = diff(a, b);
-    pythagoras::theorem(diff.0, diff.1) as u32
+    (a - b).length() as u32
 }
 
 /**
@@ -139,8 +138,8 @@ pub fn gen_radius(coord: Coordinate, min: u32, max: u32) -> Coordinate {
     let angle = roll(0u32, 3600u32);
     let a: f64 = f64::consts::PI * 0.001 * f64::from(angle);
 
-    let x = circle(f64::from(coord.x), a.cos()) as i16;
-    let y = circle(f64::from(coord.y), a.sin()) as i16;
+    let x = circle(f64::from(coord.x), a.cos()) as i32;
+    let y = circle(f64::from(coord.y), a.sin()) as i32;
 
     Coordinate { x, y }
 }
@@ -175,19 +174,18 @@ pub fn rotate_around_axis(axis: Coordinate, points: &mut Vec<super::Node>, deg:
     }
 
     for p in points.iter_mut() {
-        let radius = f64::from(distance(axis, p.geo));
-        let diff = p.geo - axis;
-        let base = f64::from(diff.x).atan2(f64::from(diff.y));
-        let angle = base + (deg * f64::consts::PI / 180.0);
-
-        p.geo.y = axis.y + f64::round(angle.cos() * radius) as i16;
-        p.geo.x = axis.x + f64::round(angle.sin() * radius) as i16;
+        p.geo = axis + (p.geo - axis).rotate(deg);
     }
 }
 
+/**
+Orders Coordinates by x, and then by y.
+
+Use Coordinate::cmp_sum to compare the sum of x and y instead.
+ */
 impl Ord for Coordinate {
     fn cmp(&self, other: &Coordinate) -> Ordering {
-        (self.x + self.y).cmp(&(other.x + other.y))
+        self.x.cmp(&other.x).then(self.y.cmp(&other.y))
     }
 }
 
@@ -233,6 +231,11 @@ impl std::ops::SubAssign for Coordinate {
     }
 }
 
+/**
+Multiplies the Coordinates component-wise, x with x and y with y.
+
+Use Coordinate::scale to multiply by a scalar.
+ */
 impl std::ops::Mul for Coordinate {
     type Output = Self;
 
@@ -244,10 +247,76 @@ impl std::ops::Mul for Coordinate {
     }
 }
 
+/**
+A value that can be used as the x or y of a Coordinate, used by the coordinate! macro.
+
+Floats are truncated towards zero.
+
+
+## Panics
+
+If the value does not fit in an i32, or is not a number.
+ */
+pub trait Component {
+    fn to_i32(self) -> i32;
+}
+
+macro_rules! component_int {
+    ($($t:ty),*) => {
+        $(
+            impl Component for $t {
+                fn to_i32(self) -> i32 {
+                    match i32::try_from(self) {
+                        Ok(value) => value,
+                        Err(_) => panic!("coordinate value {} does not fit in an i32", self),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+component_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Component for f64 {
+    fn to_i32(self) -> i32 {
+        if self > f64::from(i32::MIN) - 1.0 && self < f64::from(i32::MAX) + 1.0 {
+            self as i32
+        } else {
+            panic!("coordinate value {} does not fit in an i32", self)
+        }
+    }
+}
+
+impl Component for f32 {
+    fn to_i32(self) -> i32 {
+        f64::from(self).to_i32()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{super::Node, *};
 
+    #[test]
+    fn test_component() {
+        assert_eq!(300u64.to_i32(), 300);
+        assert_eq!((-2.7f64).to_i32(), -2);
+        assert_eq!(1.5f32.to_i32(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in an i32")]
+    fn test_component_overflow() {
+        u32::MAX.to_i32();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in an i32")]
+    fn test_component_nan() {
+        f64::NAN.to_i32();
+    }
+
     #[test]
     fn test_eq() {
         let co1: Coordinate = Coordinate::new(1, 1);
@@ -268,6 +337,32 @@ mod tests {
         assert!(co1 < co3);
     }
 
+    #[test]
+    fn test_ord_lexicographic() {
+        let co1: Coordinate = Coordinate::new(0, 10);
+        let co2: Coordinate = Coordinate::new(10, 0);
+        assert!(co1 < co2);
+        assert_eq!(co1.cmp_sum(&co2), Ordering::Equal);
+
+        let mut v = vec![co2, Coordinate::new(0, 5), co1];
+        v.sort();
+        assert_eq!(v, vec![Coordinate::new(0, 5), co1, co2]);
+    }
+
+    #[test]
+    fn test_ord_btree_map() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(Coordinate::new(0, 10), "a");
+        map.insert(Coordinate::new(10, 0), "b");
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_mul_component_wise() {
+        let co1: Coordinate = Coordinate::new(2, 3);
+        assert_eq!(co1 * Coordinate::new(4, 5), Coordinate::new(8, 15));
+    }
+
     #[test]
     fn test_gen_within_radius() {
         // Default
@@ -381,6 +476,15 @@ mod tests {
         assert!(diff(co2, co3) == diff(co3, co2));
     }
 
+    #[test]
+    fn test_diff_beyond_i16() {
+        let co1: Coordinate = Coordinate::new(-40000, 0);
+        let co2: Coordinate = Coordinate::new(40000, 0);
+
+        assert_eq!(diff(co1, co2), (80000, 0));
+        assert_eq!(distance(co1, co2), 80000);
+    }
+
     #[test]
     fn test_clone() {
         let co1: Coordinate = Coordinate::new(1, 1);
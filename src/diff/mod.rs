@@ -0,0 +1,439 @@
+/*!
+Compares two snapshots of a network.
+
+Nodes are matched using their hashes, so a Node keeps its identity as long as its name is unchanged.
+ */
+
+use super::{
+    consts, map, map::timeline::Easing, tools, Coordinate, Draw, Group, Layer, Map, Node, Sink, HL,
+};
+use image::Rgba;
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+/**
+Color of Nodes and edges that only exist in the second snapshot.
+ */
+pub const ADDED: Rgba<u8> = Rgba {
+    data: [40, 170, 60, 255],
+};
+
+/**
+Color of Nodes and edges that only exist in the first snapshot.
+ */
+pub const REMOVED: Rgba<u8> = Rgba {
+    data: [210, 40, 40, 255],
+};
+
+/**
+Color of Nodes that exist in both snapshots, but at different positions.
+ */
+pub const MOVED: Rgba<u8> = Rgba {
+    data: [240, 160, 20, 255],
+};
+
+/**
+Color of Nodes that are the same in both snapshots.
+ */
+pub const UNCHANGED: Rgba<u8> = Rgba {
+    data: [130, 130, 130, 255],
+};
+
+/**
+The changes between two snapshots of Nodes.
+
+Moved Nodes are stored as pairs of the Node before and after it moved.
+
+
+## Examples
+
+```
+# use pathtracer::{diff::Diff, *};
+let before = Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20)]));
+let mut after = Node::linked_list(Node::from_list(&[(0, 0), (10, 15)]));
+after.push(Node::new("D", Coordinate::new(30, 30)));
+
+let diff = Diff::new(&before, &after);
+assert_eq!(diff.added.len(), 1);
+assert_eq!(diff.removed.len(), 1);
+assert_eq!(diff.moved.len(), 1);
+assert_eq!(diff.removed_edges.len(), 1);
+```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Diff {
+    pub added: Vec<Node>,
+    pub removed: Vec<Node>,
+    pub moved: Vec<(Node, Node)>,
+    pub unchanged: Vec<Node>,
+    pub added_edges: Vec<HL>,
+    pub removed_edges: Vec<HL>,
+}
+
+impl Diff {
+    /**
+    Compares two lists of Nodes.
+
+    Edges are matched using the hashes of both of their endpoints.
+     */
+    pub fn new(before: &[Node], after: &[Node]) -> Self {
+        let old = before
+            .iter()
+            .map(|n| (n.hash, *n))
+            .collect::<HashMap<_, _>>();
+        let new = after.iter().map(|n| n.hash).collect::<HashSet<_>>();
+        let mut diff = Diff::default();
+
+        for node in after.iter() {
+            match old.get(&node.hash) {
+                None => diff.added.push(*node),
+                Some(prev) if prev.geo != node.geo => diff.moved.push((*prev, *node)),
+                Some(_) => diff.unchanged.push(*node),
+            }
+        }
+        diff.removed = before
+            .iter()
+            .filter(|n| !new.contains(&n.hash))
+            .cloned()
+            .collect();
+
+        let old_edges = edges(before);
+        let new_edges = edges(after);
+        let key = |l: &HL| (l.f, l.t);
+        let old_keys = old_edges.iter().map(key).collect::<HashSet<_>>();
+        let new_keys = new_edges.iter().map(key).collect::<HashSet<_>>();
+        diff.added_edges = new_edges
+            .into_iter()
+            .filter(|l| !old_keys.contains(&key(l)))
+            .collect();
+        diff.removed_edges = old_edges
+            .into_iter()
+            .filter(|l| !new_keys.contains(&key(l)))
+            .collect();
+        diff
+    }
+
+    /**
+    Compares the Nodes inside two lists of Groups.
+
+    The Nodes are compared at the positions they are drawn at, including the position of their Group.
+     */
+    pub fn from_groups(before: &[Group], after: &[Group]) -> Self {
+        Diff::new(&flatten(before), &flatten(after))
+    }
+
+    /**
+    Returns true if the snapshots are the same.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+
+    /**
+    Maps both snapshots on to a single image, coloring the changes.
+
+    Moved Nodes are drawn at both positions, with a trail leading to where they moved.
+    Only the Nodes have Hitboxes, the trails and the colored edges do not.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{diff::Diff, *};
+    let before = Node::from_list(&[(0, 0), (10, 10)]);
+    let after = Node::from_list(&[(0, 0), (20, 10)]);
+    let map = Diff::new(&before, &after).map();
+    let offset = map.offset();
+    let image = map.consume();
+
+    let pixel = |c: Coordinate| {
+        let c = c + offset;
+        *image.image().get_pixel(c.x as u32, c.y as u32)
+    };
+    assert_eq!(pixel(Coordinate::new(1, 1)), diff::UNCHANGED);
+    assert_eq!(pixel(Coordinate::new(21, 11)), diff::MOVED);
+    ```
+     */
+    pub fn map(&self) -> Map {
+        let color = |n: &Node, c| {
+            let mut n = *n;
+            n.color = c;
+            n
+        };
+
+        let mut nodes = Vec::new();
+        nodes.extend(self.unchanged.iter().map(|n| color(n, UNCHANGED)));
+        nodes.extend(self.removed.iter().map(|n| color(n, REMOVED)));
+        nodes.extend(self.added.iter().map(|n| color(n, ADDED)));
+
+        let mut overlay = Vec::new();
+        for (prev, next) in self.moved.iter() {
+            let mut ghost = color(prev, UNCHANGED);
+            ghost.disconnect();
+            nodes.push(ghost);
+            nodes.push(color(next, MOVED));
+            overlay.extend(trail(&tools::plot(prev.geo, next.geo), MOVED));
+        }
+        for edge in self.added_edges.iter() {
+            overlay.extend(trail(&edge.plot(), ADDED));
+        }
+        for edge in self.removed_edges.iter() {
+            overlay.extend(trail(&edge.plot(), REMOVED));
+        }
+
+        Map::new().map(&nodes).map_marks(Layer::Overlay, &overlay)
+    }
+
+    /**
+    Returns the Nodes as they are part way through changing from the first to the second snapshot.
+
+    Removed Nodes shrink, added Nodes grow and moved Nodes travel to their new position.
+    Edges follow the Nodes they connect.
+     */
+    pub fn morph_at(&self, t: f64) -> Vec<Node> {
+        let t = Easing::EaseInOut.apply(t);
+        let resize = |n: &Node, from: f64, to: f64| {
+            let mut n = *n;
+            n.radius = Some((f64::from(n.size()) * (from + (to - from) * t)).round() as u32);
+            n
+        };
+
+        let mut nodes = self.unchanged.clone();
+        for (prev, next) in self.moved.iter() {
+            let mut n = *next;
+            n.geo = prev.geo.lerp(next.geo, t);
+            nodes.push(n);
+        }
+        if t < 1.0 {
+            nodes.extend(self.removed.iter().map(|n| resize(n, 1.0, 0.0)));
+        }
+        if t > 0.0 {
+            nodes.extend(self.added.iter().map(|n| resize(n, 0.0, 1.0)));
+        }
+
+        let positions = nodes
+            .iter()
+            .map(|n| (n.hash, n.geo))
+            .collect::<HashMap<_, _>>();
+        for node in nodes.iter_mut() {
+            let geo = node.geo;
+            for link in node.links.iter_mut().filter(|l| l.is_connected()) {
+                link.from = Some(geo);
+                if let Some(&to) = positions.get(&link.t) {
+                    link.to = Some(to);
+                }
+            }
+        }
+        nodes
+    }
+
+    /**
+    Pushes an animation of the first snapshot changing in to the second on to the Sink, mapping one frame at a time.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{diff::Diff, map::gif::Gif, *};
+    # fn main() -> std::io::Result<()> {
+    let before = Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20)]));
+    let after = Node::linked_list(Node::from_list(&[(0, 0), (30, 10)]));
+    let mut gif = Gif::new("/tmp/morph.gif", 50, 50);
+    Diff::new(&before, &after).morph(&mut gif, 10)?;
+    assert_eq!(gif.frames(), 10);
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If there are fewer than two frames, since the first and the last frame show each snapshot,
+    or if the Sink fails to write a frame.
+     */
+    pub fn morph<S: Sink>(&self, sink: &mut S, frames: usize) -> Result<(), io::Error> {
+        if frames < 2 {
+            return Err(io::Error::other("a morph requires at least two frames"));
+        }
+        let last = frames - 1;
+        for map in map::gen_frames_with(frames, |i| self.morph_at(i as f64 / last as f64)) {
+            sink.push(map)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+Returns all connected edges of the Nodes.
+ */
+fn edges(nodes: &[Node]) -> Vec<HL> {
+    nodes
+        .iter()
+        .flat_map(|n| n.links.iter().filter(|l| l.is_connected()).cloned())
+        .collect()
+}
+
+/**
+Returns the Nodes of the Groups, moved to the positions they are drawn at.
+ */
+fn flatten(groups: &[Group]) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for group in groups.iter() {
+        let offset = group.settings.geo;
+        for node in group.nodes.iter() {
+            let mut node = *node;
+            node.geo += offset;
+            for link in node.links.iter_mut() {
+                link.from = link.from.map(|c| c + offset);
+                link.to = link.to.map(|c| c + offset);
+            }
+            nodes.push(node);
+        }
+    }
+    nodes
+}
+
+/**
+Returns small Nodes covering the Coordinates, used to color edges.
+ */
+fn trail(plot: &[Coordinate], color: Rgba<u8>) -> Vec<Node> {
+    plot.iter()
+        .map(|&c| {
+            let mut node = Node::from(c);
+            node.color = color;
+            node.radius = Some(u32::from(consts::DEFAULT_LINK_SIZE));
+            node
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn before() -> Vec<Node> {
+        Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20), (30, 30)]))
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let diff = Diff::new(&before(), &before());
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged.len(), 4);
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let before = before();
+        let after = before[..2].to_vec();
+        let diff = Diff::new(&after, &before);
+        assert_eq!(diff.added.len(), 2);
+        assert_eq!(diff.added_edges.len(), 2);
+
+        let diff = Diff::new(&before, &after);
+        assert_eq!(diff.removed.len(), 2);
+        assert_eq!(diff.removed_edges.len(), 2);
+    }
+
+    #[test]
+    fn test_moved() {
+        let before = before();
+        let mut after = before.clone();
+        after[1].geo = Coordinate::new(15, 5);
+        let diff = Diff::new(&before, &after);
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].0.geo, Coordinate::new(10, 10));
+        assert_eq!(diff.moved[0].1.geo, Coordinate::new(15, 5));
+        assert!(diff.added_edges.is_empty());
+    }
+
+    #[test]
+    fn test_from_groups_uses_drawn_position() {
+        let mut a = Group::new("A", Coordinate::new(0, 0));
+        a.push(Node::new("a", Coordinate::new(5, 5)));
+        let mut b = a.clone();
+        b.settings.geo = Coordinate::new(10, 0);
+
+        let diff = Diff::from_groups(&[a], &[b]);
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].1.geo.x - diff.moved[0].0.geo.x, 10);
+    }
+
+    #[test]
+    fn test_morph_frames() -> std::io::Result<()> {
+        let diff = Diff::new(&before(), &before()[1..]);
+        let mut gif = crate::map::gif::Gif::from_writer(Vec::new(), 50, 50)?;
+        assert!(diff.morph(&mut gif, 1).is_err());
+        assert_eq!(gif.frames(), 0);
+        diff.morph(&mut gif, 2)?;
+        assert_eq!(gif.frames(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_morph_edges_follow_nodes() {
+        let before = before();
+        let mut after = before.clone();
+        after[1].geo = Coordinate::new(10, 30);
+        let diff = Diff::new(&before, &after);
+
+        let end = diff.morph_at(1.0);
+        let find = |i: usize| end.iter().find(|n| n.hash == before[i].hash).unwrap();
+        assert_eq!(find(1).links[0].from, Some(Coordinate::new(10, 30)));
+        assert_eq!(find(2).links[0].to, Some(Coordinate::new(10, 30)));
+    }
+
+    #[test]
+    fn test_morph_removed_and_added() {
+        let before = before();
+        let after = before[..3].to_vec();
+        let diff = Diff::new(&before, &after);
+        assert_eq!(diff.morph_at(0.0).len(), 4);
+        assert_eq!(diff.morph_at(1.0).len(), 3);
+        assert_eq!(Diff::new(&after, &before).morph_at(0.0).len(), 3);
+    }
+
+    #[test]
+    fn test_map() {
+        let before = before();
+        let mut after = before[1..].to_vec();
+        after[0].geo = Coordinate::new(0, 20);
+        after.push(Node::new("E", Coordinate::new(40, 0)));
+
+        let map = Diff::new(&before, &after).map();
+        let offset = map.offset();
+        let image = map.consume();
+        let pixel = |c: Coordinate| {
+            let c = c + offset;
+            *image.image().get_pixel(c.x as u32, c.y as u32)
+        };
+
+        // Off the center lines of the Nodes, which are drawn in a lighter shade.
+        assert_eq!(pixel(Coordinate::new(1, 1)), REMOVED);
+        assert_eq!(pixel(Coordinate::new(1, 21)), MOVED);
+        assert_eq!(pixel(Coordinate::new(41, 1)), ADDED);
+        assert_eq!(pixel(Coordinate::new(31, 31)), UNCHANGED);
+        // The trail from where the Node moved from to where it is now, drawn with the center shade of a Node.
+        let shade = consts::DEFAULT_SHADE as u8;
+        let mut trail = MOVED;
+        trail.data[..3]
+            .iter_mut()
+            .for_each(|x| *x = x.saturating_add(shade));
+        assert_eq!(pixel(Coordinate::new(5, 15)), trail);
+    }
+
+    #[test]
+    fn test_map_hitboxes_only_nodes() {
+        let before = Node::from_list(&[(0, 0), (10, 10)]);
+        let after = Node::from_list(&[(0, 0), (20, 10)]);
+        let map = Diff::new(&before, &after).map();
+        // The unchanged Node, and the moved Node at both of its positions.
+        assert_eq!(map.hitboxes().len(), 3);
+    }
+}
@@ -0,0 +1,401 @@
+/*!
+Geographic coordinates and map projections.
+
+Converts latitude and longitude positions into Coordinates, so real world
+locations can be placed on a Map without converting them by hand.
+ */
+
+use super::{Coordinate, Node};
+use std::f64::consts::PI;
+
+/**
+Mean radius of the earth in meters, used for haversine distances.
+ */
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/**
+The largest latitude that can be projected using Web Mercator.
+ */
+pub const MERCATOR_MAX_LAT: f64 = 85.051_128_78;
+
+/**
+A position on the earth in degrees.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct LatLon {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/**
+Decides how the curved surface of the earth is flattened on to the image.
+
+
+## Mercator
+
+Web Mercator, as used by most online maps. Preserves angles but enlarges areas close to the poles.
+
+
+## Equirectangular
+
+Maps longitude and latitude linearly to x and y.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Projection {
+    Mercator,
+    Equirectangular,
+}
+
+/**
+A bounding box of the world rendered at a given resolution.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Viewport {
+    pub projection: Projection,
+    pub min: LatLon,
+    pub max: LatLon,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl LatLon {
+    /**
+    Constructs a LatLon struct.
+     */
+    pub fn new(lat: f64, lon: f64) -> Self {
+        LatLon { lat, lon }
+    }
+}
+
+impl std::fmt::Display for LatLon {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}, {}", self.lat, self.lon)
+    }
+}
+
+impl Projection {
+    /**
+    Projects a position on to a plane where the world spans 0 to 1 horizontally,
+    and 0, 0 is the north-west corner of the world.
+
+    Distances are kept at the same scale on both axes,
+    so the world is 1 high using Mercator and 0.5 high using Equirectangular.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::geo::*;
+    let (x, y) = Projection::Mercator.project(LatLon::new(0.0, 0.0));
+    assert_eq!((x, y), (0.5, 0.5));
+    ```
+     */
+    pub fn project(self, pos: LatLon) -> (f64, f64) {
+        let x = (pos.lon + 180.0) / 360.0;
+        let y = match self {
+            Projection::Mercator => {
+                let lat = pos.lat.clamp(-MERCATOR_MAX_LAT, MERCATOR_MAX_LAT);
+                let rad = lat.to_radians();
+                (1.0 - (rad.tan() + 1.0 / rad.cos()).ln() / PI) / 2.0
+            }
+            Projection::Equirectangular => (90.0 - pos.lat) / 360.0,
+        };
+        (x, y)
+    }
+
+    /**
+    Reverses project, converting a projected position back to a LatLon.
+     */
+    pub fn unproject(self, x: f64, y: f64) -> LatLon {
+        let lon = x * 360.0 - 180.0;
+        let lat = match self {
+            Projection::Mercator => (PI * (1.0 - 2.0 * y)).sinh().atan().to_degrees(),
+            Projection::Equirectangular => 90.0 - y * 360.0,
+        };
+        LatLon { lat, lon }
+    }
+}
+
+impl Viewport {
+    /**
+    Constructs a Viewport covering the area between two corners.
+
+    The height is calculated from the width to keep the aspect ratio of the projection.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::geo::*;
+    let a = LatLon::new(55.0, 10.0);
+    let b = LatLon::new(69.0, 25.0);
+    let viewport = Viewport::new(Projection::Equirectangular, a, b, 300);
+    assert_eq!(viewport.height, 280);
+    ```
+
+
+    ## Panics
+
+    If the corners share a latitude or longitude, since the area between them is empty once projected.
+    This includes latitudes beyond the ones Mercator clamps to.
+     */
+    pub fn new(projection: Projection, a: LatLon, b: LatLon, width: u32) -> Self {
+        let min = LatLon::new(a.lat.min(b.lat), a.lon.min(b.lon));
+        let max = LatLon::new(a.lat.max(b.lat), a.lon.max(b.lon));
+        let (x1, y1) = projection.project(LatLon::new(max.lat, min.lon));
+        let (x2, y2) = projection.project(LatLon::new(min.lat, max.lon));
+        assert!(
+            x2 > x1 && y2 > y1,
+            "Viewport between {} and {} covers no area",
+            a,
+            b
+        );
+        let height = (f64::from(width) * (y2 - y1) / (x2 - x1)).round() as u32;
+
+        Viewport {
+            projection,
+            min,
+            max,
+            width,
+            height,
+        }
+    }
+
+    /**
+    Constructs a Viewport covering the entire world.
+     */
+    pub fn world(projection: Projection, width: u32) -> Self {
+        let lat = match projection {
+            Projection::Mercator => MERCATOR_MAX_LAT,
+            Projection::Equirectangular => 90.0,
+        };
+        Viewport::new(
+            projection,
+            LatLon::new(-lat, -180.0),
+            LatLon::new(lat, 180.0),
+            width,
+        )
+    }
+
+    /**
+    Returns the projected corners of the Viewport, as (x1, y1, x2, y2).
+     */
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (x1, y1) = self
+            .projection
+            .project(LatLon::new(self.max.lat, self.min.lon));
+        let (x2, y2) = self
+            .projection
+            .project(LatLon::new(self.min.lat, self.max.lon));
+        (x1, y1, x2, y2)
+    }
+
+    /**
+    Converts a LatLon to a Coordinate on the image the Viewport covers.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{geo::*, Coordinate};
+    let viewport = Viewport::world(Projection::Equirectangular, 360);
+    let c = viewport.coordinate(LatLon::new(0.0, 0.0));
+    assert_eq!(c, Coordinate::new(180, 90));
+    ```
+     */
+    pub fn coordinate(&self, pos: LatLon) -> Coordinate {
+        let (x1, y1, x2, y2) = self.bounds();
+        let (x, y) = self.projection.project(pos);
+        let x = (x - x1) / (x2 - x1) * f64::from(self.width);
+        let y = (y - y1) / (y2 - y1) * f64::from(self.height);
+        Coordinate::new(x.round() as i32, y.round() as i32)
+    }
+
+    /**
+    Converts a Coordinate on the image the Viewport covers back to a LatLon.
+     */
+    pub fn lat_lon(&self, c: Coordinate) -> LatLon {
+        let (x1, y1, x2, y2) = self.bounds();
+        let x = x1 + f64::from(c.x) / f64::from(self.width) * (x2 - x1);
+        let y = y1 + f64::from(c.y) / f64::from(self.height) * (y2 - y1);
+        self.projection.unproject(x, y)
+    }
+
+    /**
+    Returns true if the position is inside the Viewport.
+     */
+    pub fn contains(&self, pos: LatLon) -> bool {
+        pos.lat >= self.min.lat
+            && pos.lat <= self.max.lat
+            && pos.lon >= self.min.lon
+            && pos.lon <= self.max.lon
+    }
+
+    /**
+    Constructs a Node positioned at the given LatLon.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::geo::*;
+    let viewport = Viewport::world(Projection::Mercator, 512);
+    let node = viewport.node("Stockholm", LatLon::new(59.33, 18.07));
+    assert_eq!(node.geo, viewport.coordinate(LatLon::new(59.33, 18.07)));
+    ```
+     */
+    pub fn node(&self, name: &str, pos: LatLon) -> Node {
+        Node::new(name, self.coordinate(pos))
+    }
+
+    /**
+    Returns the haversine distance in meters between two Coordinates on the image.
+
+    Useful as a weight when pathing through a Network placed using the Viewport.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{geo::*, *};
+    let viewport = Viewport::world(Projection::Mercator, 1024);
+    let nodes = vec![
+        viewport.node("A", LatLon::new(59.33, 18.07)),
+        viewport.node("B", LatLon::new(57.71, 11.97)),
+    ];
+    let path = Network::new(Node::linked_list(nodes))
+        .path_weighted("A", "B", &|a, b| viewport.distance(a, b))
+        .unwrap();
+    assert_eq!(path.len(), 2);
+    ```
+     */
+    pub fn distance(&self, a: Coordinate, b: Coordinate) -> u32 {
+        haversine(self.lat_lon(a), self.lat_lon(b)) as u32
+    }
+}
+
+/**
+Returns the great-circle distance in meters between two positions.
+
+
+## Examples
+
+```
+# use pathtracer::geo::*;
+let stockholm = LatLon::new(59.3293, 18.0686);
+let gothenburg = LatLon::new(57.7089, 11.9746);
+let d = haversine(stockholm, gothenburg);
+assert!(d > 390_000.0 && d < 400_000.0);
+```
+ */
+pub fn haversine(a: LatLon, b: LatLon) -> f64 {
+    let d_lat = (b.lat - a.lat).to_radians();
+    let d_lon = (b.lon - a.lon).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2)
+        + a.lat.to_radians().cos() * b.lat.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_project_origin() {
+        let (x, y) = Projection::Mercator.project(LatLon::new(0.0, 0.0));
+        assert!(close(x, 0.5) && close(y, 0.5));
+        let (x, y) = Projection::Equirectangular.project(LatLon::new(0.0, 0.0));
+        assert!(close(x, 0.5) && close(y, 0.25));
+    }
+
+    #[test]
+    fn test_project_round_trip() {
+        let pos = LatLon::new(48.8566, 2.3522);
+        for p in [Projection::Mercator, Projection::Equirectangular].iter() {
+            let (x, y) = p.project(pos);
+            let back = p.unproject(x, y);
+            assert!(close(pos.lat, back.lat) && close(pos.lon, back.lon));
+        }
+    }
+
+    #[test]
+    fn test_mercator_clamps_poles() {
+        let (_, y) = Projection::Mercator.project(LatLon::new(90.0, 0.0));
+        assert!(close(y, 0.0));
+    }
+
+    #[test]
+    fn test_world_viewport_is_square_in_mercator() {
+        let viewport = Viewport::world(Projection::Mercator, 256);
+        assert_eq!(viewport.height, 256);
+    }
+
+    #[test]
+    fn test_world_viewport_is_wide_in_equirectangular() {
+        let viewport = Viewport::world(Projection::Equirectangular, 360);
+        assert_eq!(viewport.height, 180);
+    }
+
+    #[test]
+    fn test_viewport_corners() {
+        let viewport = Viewport::world(Projection::Equirectangular, 360);
+        assert_eq!(
+            viewport.coordinate(LatLon::new(90.0, -180.0)),
+            Coordinate::new(0, 0)
+        );
+        assert_eq!(
+            viewport.coordinate(LatLon::new(-90.0, 180.0)),
+            Coordinate::new(360, 180)
+        );
+    }
+
+    #[test]
+    fn test_viewport_contains() {
+        let viewport = Viewport::new(
+            Projection::Mercator,
+            LatLon::new(55.0, 10.0),
+            LatLon::new(69.0, 25.0),
+            100,
+        );
+        assert!(viewport.contains(LatLon::new(59.33, 18.07)));
+        assert!(!viewport.contains(LatLon::new(48.85, 2.35)));
+    }
+
+    #[test]
+    #[should_panic(expected = "covers no area")]
+    fn test_viewport_same_longitude() {
+        Viewport::new(
+            Projection::Equirectangular,
+            LatLon::new(55.0, 10.0),
+            LatLon::new(69.0, 10.0),
+            100,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "covers no area")]
+    fn test_viewport_beyond_mercator() {
+        Viewport::new(
+            Projection::Mercator,
+            LatLon::new(86.0, 10.0),
+            LatLon::new(89.0, 25.0),
+            100,
+        );
+    }
+
+    #[test]
+    fn test_haversine_zero() {
+        let pos = LatLon::new(12.0, 34.0);
+        assert!(close(haversine(pos, pos), 0.0));
+    }
+
+    #[test]
+    fn test_haversine_quarter_circumference() {
+        let d = haversine(LatLon::new(0.0, 0.0), LatLon::new(0.0, 90.0));
+        assert!(close(d, EARTH_RADIUS * PI / 2.0));
+    }
+}
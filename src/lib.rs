@@ -2,6 +2,7 @@
 
 extern crate gif;
 extern crate image;
+extern crate png;
 extern crate pythagoras;
 extern crate rand;
 
@@ -11,12 +12,16 @@ extern crate log;
 #[macro_use]
 pub mod macros;
 
+pub mod color;
 pub mod consts;
 pub mod coordinate;
 pub mod data;
+pub mod diff;
+pub mod geo;
 pub mod group;
 pub mod map;
 pub mod node;
+pub mod spatial;
 pub mod tools;
 pub mod traits;
 
@@ -34,8 +39,19 @@ differennt structures to be drawn.
  */
 #[derive(Debug, Eq, Copy, Clone, Default)]
 pub struct Coordinate {
-    pub x: i16,
-    pub y: i16,
+    pub x: i32,
+    pub y: i32,
+}
+
+/**
+An axis aligned rectangle between a minimum and maximum Coordinate.
+
+Used for the bounds of structures, such as the area a Node occupies when drawn.
+ */
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Rect {
+    pub min: Coordinate,
+    pub max: Coordinate,
 }
 
 /*
@@ -57,6 +73,9 @@ But does not have any direct impact on the HL since it only stores a Hash refere
 #[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
 pub struct HL {
     pub style: EdgeStyle,
+    pub arrow: Arrow,
+    pub stroke: Stroke,
+    pub color: Option<image::Rgba<u8>>,
     pub f: u64,
     pub t: u64,
     pub from: Option<Coordinate>,
@@ -72,6 +91,10 @@ pub struct Node {
     pub geo: Coordinate,
     pub color: image::Rgba<u8>,
     pub radius: Option<u32>,
+    pub shape: Option<Shape>,
+    pub border: Option<u32>,
+    pub sprite: Option<Sprite>,
+    pub z: i32,
     links: [HL; consts::MAX_LINKS],
 }
 
@@ -95,6 +118,33 @@ High abstraction Map which helps position objects.
 pub struct Map {
     image: Option<IW>,
     add: Coordinate,
+    sprites: std::collections::HashMap<u64, std::sync::Arc<image::RgbaImage>>,
+    background: Option<image::Rgba<u8>>,
+    hitboxes: Vec<map::hitbox::Hitbox>,
+}
+
+/**
+Reasons a Map has no image to output.
+
+Converts in to a std::io::Error, so it can be retrieved from the errors of Map::save and Map::write_to.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+let nodes: Vec<Node> = Vec::new();
+let err = Map::new().map(&nodes).save(std::path::Path::new("/tmp/empty.png")).unwrap_err();
+let reason = err.get_ref().and_then(|e| e.downcast_ref::<MapError>());
+assert_eq!(reason, Some(&MapError::Empty));
+```
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MapError {
+    /// Nothing has been mapped, and no canvas has been given, so the size of the image is not known.
+    Empty,
+    /// The canvas has no width or no height.
+    ZeroSized,
 }
 
 /**
@@ -129,60 +179,406 @@ Curves the connection
 
 Uses Brasehem's line algorithm to directly correct the nodes.
 
+
+## Quadratic
+
+A quadratic Bézier curve, bending to the side.
+The control point is placed at the middle of the edge, moved a percentage of the length of the edge to the side.
+Negative values bend to the other side.
+
+
+## Cubic
+
+A cubic Bézier curve in an S shape, with the two control points moved a percentage of the length of the edge
+to opposite sides.
+
+
+## CatmullRom
+
+A smooth curve passing through a point, given as an offset from the middle of the edge.
+
+
+## Orthogonal
+
+Horizontal and vertical lines routed around the boxes of other elements on the Map.
+HL::plot does not know of the other elements, and routes without avoiding them.
+
+
+## Bundled
+
+Gathers edges going between the same areas in to bundles, making dense graphs easier to read.
+The strength is from 0, a straight line, to 100, tightly bundled.
+Check out tools::plot_bundled for details.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+# fn main() -> std::io::Result<()> {
+let b = Node::new("B", Coordinate::new(0, 0));
+let mut a = Node::new("A", Coordinate::new(60, 20));
+a.link(&b);
+a.hl_mut(0)?.style(EdgeStyle::Quadratic(30));
+let image = Map::new().map(&[a, b]).consume();
+# Ok(())
+# }
+```
  */
-#[derive(Copy, PartialEq, Eq, Clone, Debug)]
+#[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
 pub enum EdgeStyle {
+    #[default]
     Direct,
     Ellipse,
     Straight,
+    Quadratic(i32),
+    Cubic(i32),
+    CatmullRom(Coordinate),
+    Orthogonal,
+    Bundled(u8),
+}
+
+/**
+Markers drawn on an edge to show which way it goes, from the Node it belongs to towards the Node it links to.
+
+By default nothing is drawn, and the edge ends at the center of the Node it links to.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+# fn main() -> std::io::Result<()> {
+let b = Node::new("B", Coordinate::new(0, 0));
+let mut a = Node::new("A", Coordinate::new(40, 20));
+a.link(&b);
+a.hl_mut(0)?.arrow(Arrow::new().head(6).chevrons(1).clip_to(&b));
+let image = Map::new().map(&[a, b]).consume();
+# Ok(())
+# }
+```
+ */
+#[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Arrow {
+    pub head: u32,
+    pub open: bool,
+    pub chevrons: u32,
+    pub clip: u32,
+    pub shape: Option<Shape>,
+}
+
+/**
+How the line of an edge is drawn, solid or broken up in to a repeating pattern.
+
+Lengths are measured in steps along the edge, which are about a pixel each.
+Markers from the Arrow of the edge are always drawn solid.
+
+
+## Dashed
+
+Dashes of the first length, separated by gaps of the second length.
+
+
+## Dotted
+
+A dot every given number of steps.
+
+
+## Pattern
+
+A 32 step pattern repeated along the edge, where a set bit is drawn, starting from the least significant bit.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+# fn main() -> std::io::Result<()> {
+let b = Node::new("B", Coordinate::new(0, 0));
+let mut a = Node::new("A", Coordinate::new(60, 20));
+a.link(&b);
+a.hl_mut(0)?.stroke(Stroke::Dashed(6, 3));
+let image = Map::new().map(&[a, b]).consume();
+# Ok(())
+# }
+```
+ */
+#[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
+pub enum Stroke {
+    #[default]
+    Solid,
+    Dashed(u32, u32),
+    Dotted(u32),
+    Pattern(u32),
+}
+
+/**
+The layers a Map is drawn in, from the bottom to the top.
+
+Everything on a layer is drawn on top of the layers beneath it, no matter the order it was mapped in.
+Within a layer, elements with a higher z-index are drawn on top of those with a lower one,
+and elements with the same z-index are drawn in the order they were mapped.
+Pixels replace the ones beneath them on the same layer and z-index, even if they are semi-transparent.
+Semi-transparent pixels are blended with the layers and z-indexes beneath them, as are Sprites.
+
+
+## Background
+
+Densities from Map::map_density, drawn on top of the canvas or background image.
+
+
+## Edges
+
+The edges of every Node.
+
+
+## Nodes
+
+The Nodes themselves, as shapes or sprites.
+
+
+## Labels and Overlay
+
+Elements mapped using Map::map_layer, and the Legend on the Overlay.
+
+
+## Examples
+
+Node A is mapped last, but its edge is still drawn beneath Node B.
+
+```
+# use pathtracer::*;
+# fn main() -> std::io::Result<()> {
+let b = Node::new("B", Coordinate::new(0, 0));
+let mut a = Node::new("A", Coordinate::new(20, 0));
+a.link(&b);
+let map = Map::new().map(&[b]).map(&[a]);
+let at = b.geo + map.offset();
+let image = map.consume();
+assert_eq!(image.image().get_pixel(at.x as u32, at.y as u32), &b.color);
+# Ok(())
+# }
+```
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    Background,
+    Edges,
+    Nodes,
+    Labels,
+    Overlay,
+}
+
+/**
+Draws a Node as an image, centered at its position, instead of as a Shape.
+
+The Sprite refers to an image added to the Map using Map::sprite, by name.
+Nodes with a Sprite that has not been added to the Map are drawn as usual.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprite {
+    pub hash: u64,
+    pub scale: f64,
 }
 
 /**
  Creates a shape of coordinate points.
 
  Sampled from a Area.
+
+
+ ## Circle and Ring
+
+ Centered on the position of the Node, with the size as the radius.
+ The Ring is a Circle with a hole in the middle.
+
+
+ ## Square, Triangle, Diamond, Hexagon, Star and RoundedRect
+
+ Fitted in to a square with the size as its width, starting at the position of the Node.
+
+
+ ## Custom
+
+ A function given the size, returning the coordinates the shape occupies.
+ Closures that do not capture anything can be used as the function.
+ When drawn, the coordinates are reused for every Node of the same size, so the function should always return the same
+ coordinates for a size.
+
+
+ ## Examples
+
+ ```
+ # use pathtracer::*;
+ let mut a = Node::new("A", Coordinate::new(0, 0));
+ a.shape = Some(Shape::Star);
+ a.radius = Some(9);
+ let mut b = Node::new("B", Coordinate::new(20, 0));
+ b.shape = Some(Shape::Custom(|size| {
+     (0..size as i32).map(|i| Coordinate::new(i, i)).collect()
+ }));
+ let image = Map::new().map(&[a, b]).consume();
+ ```
 */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Eq)]
 pub enum Shape {
     Circle,
     Square,
     Triangle,
+    Diamond,
+    Hexagon,
+    Star,
+    Ring,
+    RoundedRect,
+    Custom(fn(usize) -> Vec<Coordinate>),
 }
 
 // ------------------------------------------------------------------
 
+impl PartialEq for Shape {
+    /**
+    Custom shapes are equal if they use the same function.
+     */
+    fn eq(&self, other: &Shape) -> bool {
+        match (self, other) {
+            (Shape::Custom(a), Shape::Custom(b)) => *a as usize == *b as usize,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
 impl Shape {
     /**
      Returns all coordinates that the shape occupies.
 
      Assumes that 0 0 is the top-left of the node.
 
+     Every coordinate is returned once, except for Custom shapes which return what their function does.
+
      ## Circle
 
      Algorithm is derived from:
      https://en.wikipedia.org/wiki/Midpoint_circle_algorithm
 
+     The midpoint algorithm finds the width of each row, which is then filled one row at a time.
+
     */
     pub fn area(&self, area: usize) -> Vec<Coordinate> {
         match *self {
             Shape::Circle => Shape::circle_area(area),
             Shape::Square => Shape::square_area(area),
             Shape::Triangle => Shape::triangle_area(area),
+            Shape::Diamond => Shape::fill(area, &|u, v| u.abs() + v.abs() <= 1.0),
+            Shape::Hexagon => {
+                let h = 3f64.sqrt();
+                Shape::fill(area, &|u, v| {
+                    v.abs() <= h / 2.0 && v.abs() + h * u.abs() <= h
+                })
+            }
+            Shape::Star => Shape::fill(area, &Shape::in_star),
+            Shape::Ring => Shape::Circle.outline(area, std::cmp::max(area as u32 / 3, 1)),
+            Shape::RoundedRect => Shape::fill(area, &|u, v| {
+                let r = 0.5;
+                let x = (u.abs() - (1.0 - r)).max(0.0);
+                let y = (v.abs() - (1.0 - r)).max(0.0);
+                x * x + y * y <= r * r
+            }),
+            Shape::Custom(f) => f(area),
         }
     }
 
-    fn circle_area(area: usize) -> Vec<Coordinate> {
+    /**
+    Returns the coordinates of the shape that are within the width of its edge, leaving the inside hollow.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let outline = Shape::Square.outline(4, 1);
+    assert_eq!(outline.len(), 12);
+    assert!(!outline.contains(&Coordinate::new(1, 1)));
+    ```
+     */
+    pub fn outline(&self, area: usize, width: u32) -> Vec<Coordinate> {
+        let mut points = self.area(area);
+        points.sort_by_key(|c| (c.x, c.y));
+        points.dedup();
+
+        // Peels off the edge one pixel at a time, what remains is the inside.
+        let mut inside = points
+            .iter()
+            .map(|c| (c.x, c.y))
+            .collect::<std::collections::HashSet<_>>();
+        for _ in 0..width {
+            let prev = inside;
+            inside = prev
+                .iter()
+                .filter(|&&(x, y)| {
+                    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                        .iter()
+                        .all(|n| prev.contains(n))
+                })
+                .cloned()
+                .collect();
+        }
+        points
+            .into_iter()
+            .filter(|c| !inside.contains(&(c.x, c.y)))
+            .collect()
+    }
+
+    /**
+    Returns the coordinates inside of a square with the area as its width, where the predicate is true.
+
+    The predicate is given the center of each pixel, scaled to be between -1 and 1.
+     */
+    fn fill(area: usize, inside: &Fn(f64, f64) -> bool) -> Vec<Coordinate> {
+        let scale = |i: usize| (2 * i + 1) as f64 / area as f64 - 1.0;
         let mut vec = Vec::new();
-        let mut pos = coordinate!((area - 1), 0);
-        let mut err: i16 = 1 - (area << 1) as i16;
-        let mut d = Coordinate::new(err, 1);
+        for y in 0..area {
+            for x in 0..area {
+                if inside(scale(x), scale(y)) {
+                    vec.push(coordinate!(x, y));
+                }
+            }
+        }
+        vec
+    }
+
+    /**
+    Checks if a point is inside of a five pointed star, pointing upwards, by counting how many of its sides are crossed.
+     */
+    fn in_star(u: f64, v: f64) -> bool {
+        let corner = |i: usize| {
+            let r = [1.0, 0.5][i % 2];
+            let a = std::f64::consts::PI * (i as f64 / 5.0 - 0.5);
+            (r * a.cos(), r * a.sin())
+        };
+        let mut inside = false;
+        for i in 0..10 {
+            let (a, b) = (corner(i), corner((i + 1) % 10));
+            if (a.1 > v) != (b.1 > v) && u < a.0 + (v - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
 
-        let q_plot = |x1, y1, x2, y2| tools::plot(coordinate!(x1, y1), coordinate!(x2, y2));
+    fn circle_area(area: usize) -> Vec<Coordinate> {
+        if area == 0 {
+            return Vec::new();
+        }
+        // The half width of every row in the lower half, found with the midpoint algorithm.
+        let mut half = vec![0; area];
+        let mut pos = coordinate!(area - 1, 0);
+        let mut err: i32 = 1 - (area << 1) as i32;
+        let mut d = Coordinate::new(err, 1);
 
         while pos.x >= pos.y {
-            for i in [1, -1].iter() {
-                vec.append(&mut q_plot(pos.x, i * pos.y, -pos.x, i * pos.y));
-                vec.append(&mut q_plot(i * pos.y, -pos.x, i * pos.y, pos.x));
+            half[pos.y as usize] = std::cmp::max(half[pos.y as usize], pos.x);
+            for w in half.iter_mut().take(pos.x as usize + 1) {
+                *w = std::cmp::max(*w, pos.y);
             }
 
             if err <= 0 {
@@ -196,6 +592,12 @@ impl Shape {
             }
         }
 
+        let r = area as i32 - 1;
+        let mut vec = Vec::new();
+        for y in -r..=r {
+            let w = half[y.unsigned_abs() as usize];
+            vec.extend((-w..=w).map(|x| coordinate!(x, y)));
+        }
         vec
     }
 
@@ -209,21 +611,17 @@ impl Shape {
     }
 
     fn triangle_area(area: usize) -> Vec<Coordinate> {
-        (0..area).fold(vec![], |mut acc, x| {
+        let mut vec = (0..area).fold(vec![], |mut acc, x| {
             acc.append(&mut tools::plot(
                 coordinate!(area / 2, 0),
                 coordinate!(x, area),
             ));
             acc
-        })
-    }
-}
-
-// ------------------------------------------------------------------
-
-impl std::default::Default for EdgeStyle {
-    fn default() -> Self {
-        EdgeStyle::Direct
+        });
+        // The lines share their first pixels.
+        vec.sort_by_key(|c| (c.y, c.x));
+        vec.dedup();
+        vec
     }
 }
 
@@ -264,6 +662,23 @@ impl std::fmt::Display for HL {
     }
 }
 
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MapError::Empty => write!(f, "no image has been mapped"),
+            MapError::ZeroSized => write!(f, "the image has no pixels"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<MapError> for std::io::Error {
+    fn from(err: MapError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, err)
+    }
+}
+
 // ------------------------------------------------------------------
 
 /**
@@ -272,8 +687,104 @@ Image wrapper around the Image crate to enable better debugging panics.
 #[derive(Clone, Debug)]
 pub struct IW {
     img: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    obstacles: std::sync::Arc<Obstacles>,
+    origin: Coordinate,
+    areas: Areas,
+    sprites: std::collections::HashMap<u64, std::sync::Arc<image::RgbaImage>>,
+    layers: std::collections::BTreeMap<Layer, Level>,
+    layered: bool,
+    target: (Layer, i32),
+    forced: Option<Layer>,
+    hits: Vec<(Drawing, map::hitbox::Hitbox)>,
+    drawing: Drawing,
+}
+
+/**
+The areas taken by the elements mapped, which orthogonal edges are routed around.
+
+Indexed by their minimum corner, so only the ones near an edge need to be checked.
+ */
+#[derive(Clone, Debug)]
+struct Obstacles {
+    index: spatial::QuadTree<Rect>,
+    size: Coordinate,
+}
+
+impl Obstacles {
+    fn new() -> Self {
+        Obstacles {
+            index: spatial::QuadTree::new(Vec::new()),
+            size: Coordinate::default(),
+        }
+    }
+
+    fn insert(&mut self, rect: Rect) {
+        let size = rect.size();
+        self.size = coordinate!(
+            std::cmp::max(self.size.x, size.x),
+            std::cmp::max(self.size.y, size.y)
+        );
+        self.index.insert(rect);
+    }
+
+    /**
+    Returns the obstacles overlapping the area.
+     */
+    fn near(&self, area: Rect) -> Vec<Rect> {
+        // An obstacle can only overlap the area if its minimum corner is at most its size before it.
+        let corners = Rect {
+            min: area.min - self.size,
+            max: area.max,
+        };
+        self.index
+            .within_rect(corners)
+            .into_iter()
+            .filter(|r| r.intersects(area))
+            .cloned()
+            .collect()
+    }
+}
+
+/**
+Orders what is drawn by the index of the element mapped, and the number of Nodes drawn before it for that element.
+ */
+type Drawing = (usize, usize);
+
+/**
+What has been drawn on a Layer, kept until the image is flattened.
+
+Pixels at the z-index first drawn on the layer are drawn directly on its image.
+Pixels at other z-indexes are recorded instead, since they may belong beneath pixels drawn before them.
+ */
+#[derive(Clone, Debug)]
+struct Level {
+    z: i32,
+    image: image::RgbaImage,
+    calls: Vec<DrawCall>,
+}
+
+/**
+Pixels drawn on a z-index one after another, which have to be ordered with the rest of their layer.
+ */
+#[derive(Clone, Debug)]
+struct DrawCall {
+    z: i32,
+    blend: bool,
+    pixels: Vec<(u32, u32, image::Rgba<u8>)>,
 }
 
+/**
+Identifies a rasterised shape by its shape, size and border width.
+ */
+type AreaKey = (std::mem::Discriminant<Shape>, usize, usize, Option<u32>);
+
+/**
+Rasterised shapes, shared with the cropped copies of an image so each shape is only rasterised once.
+ */
+type Areas = std::sync::Arc<
+    std::sync::Mutex<std::collections::HashMap<AreaKey, std::sync::Arc<Vec<Coordinate>>>>,
+>;
+
 impl IW {
     /**
     Retrieves the private image field.
@@ -288,86 +799,373 @@ impl IW {
     Set debug_assertions flag to panic for out of bounds positions with improved debugging messages.
      */
     pub fn put<L: Location>(&mut self, l: &L, color: image::Rgba<u8>) {
-        self.img.put_pixel(l.x() as u32, l.y() as u32, color);
+        self.paint(l.x() as u32, l.y() as u32, color, false);
     }
 
     /**
-    Returns a coordinate with the width and height of the image buffer.
+    Blends the color with what is drawn beneath it, instead of replacing it.
      */
-    pub fn dimensions(&self) -> Coordinate {
-        coordinate!(self.img.width(), self.img.height())
+    fn blend(&mut self, x: u32, y: u32, color: image::Rgba<u8>) {
+        self.paint(x, y, color, true);
     }
-}
-
-// ------------------------------------------------------------------
-
-impl Find for HL {}
-
-impl Find for Node {}
 
-impl Find for Group {
     /**
-    Recursively calls find as the group contains sets of Nodes.
+    Draws the pixel on the image, or on the layer targeted if the image is layered.
      */
-    fn find<H: Hash>(&self, hash: H) -> Option<Coordinate> {
-        let f = tools::find(hash.hash(), &self.nodes);
-        f.and_then(|x| Some(x.position()))
+    fn paint(&mut self, x: u32, y: u32, color: image::Rgba<u8>, blend: bool) {
+        if !self.layered {
+            return paint(&mut self.img, x, y, color, blend);
+        }
+
+        // Out of bounds pixels panic when drawn, rather than once the image is flattened.
+        let (width, height) = self.img.dimensions();
+        assert!(
+            x < width && y < height,
+            "Image index {:?} out of bounds {:?}",
+            (x, y),
+            (width, height)
+        );
+        let (layer, z) = self.target;
+        let level = self.layers.entry(layer).or_insert_with(|| Level {
+            z,
+            image: image::RgbaImage::new(width, height),
+            calls: Vec::new(),
+        });
+        if level.z == z {
+            return paint(&mut level.image, x, y, color, blend);
+        }
+        match level.calls.last_mut() {
+            Some(call) if call.z == z && call.blend == blend => call.pixels.push((x, y, color)),
+            _ => level.calls.push(DrawCall {
+                z,
+                blend,
+                pixels: vec![(x, y, color)],
+            }),
+        }
     }
-}
 
-// ------------------------------------------------------------------
+    /**
+    Applies a function which takes the image and returns it drawn on.
 
-impl MinMax for HL {
-    fn min_max(&self) -> (Coordinate, Coordinate) {
-        let zero = coordinate!();
-        let to = self.to.unwrap_or(zero);
-        (self.position(), to)
+    Lets functions written to take and return the image by value draw on a borrowed image.
+     */
+    pub fn apply(&mut self, f: &dyn Fn(IW) -> IW) {
+        let image = std::mem::replace(self, IW::from(image::RgbaImage::new(0, 0)));
+        *self = f(image);
     }
-}
 
-impl MinMax for Node {
-    fn min_max(&self) -> (Coordinate, Coordinate) {
-        let mut max = coordinate!(consts::DEFAULT_SIZE);
-        let mut min = self.position();
-        min -= max;
-        max += self.geo;
-        (min, max)
+    /**
+    Wraps an image, drawing everything on separate layers until it is flattened.
+     */
+    fn layered(img: image::RgbaImage) -> Self {
+        let mut image = IW::from(img);
+        image.layered = true;
+        image
     }
-}
 
-impl MinMax for Group {
     /**
-    Returns the the largest and smallest x and y position found in the group.
+    Sets the layer and z-index drawn to, unless every layer is being drawn on to one.
 
+    Does nothing unless the image is layered.
+     */
+    fn target(&mut self, layer: Layer, z: i32) {
+        self.target = (self.forced.unwrap_or(layer), z);
+    }
 
-    ## Examples
+    /**
+    Starts drawing the element with the given index, which orders the Hitboxes of its Nodes.
+     */
+    fn drawing(&mut self, index: usize) {
+        self.drawing = (index, 0);
+    }
 
-    ```
-    # #[macro_use] use pathtracer::*;
-    # fn main() {
-    let mut group = Group::new_simple(0, 0);
-    group.push(node!(100, 100));
-    let (min, max) = group.min_max();
-    assert_eq!(min.x, 0);
-    assert_eq!(max.x, 104);
-    # }
-    ```
-    */
-    fn min_max(&self) -> (Coordinate, Coordinate) {
-        let mut min = coordinate!(0, 0);
-        let mut max = coordinate!(0, 0);
-        for node in &self.nodes {
-            let (min2, max2) = node.min_max();
-            max.x = std::cmp::max(max.x, max2.x);
-            min.x = std::cmp::min(min.x, min2.x);
-            max.y = std::cmp::max(max.y, max2.y);
-            min.y = std::cmp::min(min.y, min2.y);
+    /**
+    Records the pixels a Node was drawn on, if it was drawn on any.
+     */
+    fn hit(&mut self, hash: u64, rect: Option<Rect>) {
+        if let Some(rect) = rect {
+            let (layer, z) = self.target;
+            self.hits.push((
+                self.drawing,
+                map::hitbox::Hitbox {
+                    hash,
+                    rect,
+                    layer,
+                    z,
+                },
+            ));
         }
-        (min + self.position(), max + self.position())
+        self.drawing.1 += 1;
     }
-}
 
-// ------------------------------------------------------------------
+    /**
+    Returns a layered copy of a part of the image, sharing the cache of shapes and the sprites.
+
+    Only the layers of edges and Nodes are copied, since those are the ones elements are drawn on when mapped,
+    and only the part of them drawn at the z-index of their image.
+    The obstacles are shared as well, along with where the part is on the image to find them by.
+     */
+    fn crop(&self, rect: Rect) -> IW {
+        let (width, height) = (rect.width() as u32 + 1, rect.height() as u32 + 1);
+        let (x, y) = (rect.min.x as u32, rect.min.y as u32);
+        let mut tile = IW::layered(image::RgbaImage::new(width, height));
+        for layer in [Layer::Edges, Layer::Nodes].iter() {
+            if let Some(level) = self.layers.get(layer) {
+                let image = image::RgbaImage::from_fn(width, height, |i, j| {
+                    *level.image.get_pixel(x + i, y + j)
+                });
+                tile.layers.insert(
+                    *layer,
+                    Level {
+                        z: level.z,
+                        image,
+                        calls: Vec::new(),
+                    },
+                );
+            }
+        }
+        tile.areas = self.areas.clone();
+        tile.sprites = self.sprites.clone();
+        tile.obstacles = self.obstacles.clone();
+        tile.origin = self.origin + rect.min;
+        tile
+    }
+
+    /**
+    Replaces a part of the layers with what was drawn on a cropped copy.
+     */
+    fn paste(&mut self, rect: Rect, tile: IW) {
+        let (x, y) = (rect.min.x as u32, rect.min.y as u32);
+        let (width, height) = self.img.dimensions();
+        for (layer, part) in tile.layers {
+            let level = self.layers.entry(layer).or_insert_with(|| Level {
+                z: part.z,
+                image: image::RgbaImage::new(width, height),
+                calls: Vec::new(),
+            });
+
+            // The part only starts out with what was on the layer if it was drawn at the same z-index.
+            if level.z == part.z {
+                for (i, j, pixel) in part.image.enumerate_pixels() {
+                    level.image.put_pixel(x + i, y + j, *pixel);
+                }
+            } else {
+                let pixels = part
+                    .image
+                    .enumerate_pixels()
+                    .filter(|(_, _, pixel)| pixel[3] > 0)
+                    .map(|(i, j, pixel)| (x + i, y + j, *pixel))
+                    .collect();
+                level.calls.push(DrawCall {
+                    z: part.z,
+                    blend: false,
+                    pixels,
+                });
+            }
+
+            for mut call in part.calls {
+                for pixel in call.pixels.iter_mut() {
+                    pixel.0 += x;
+                    pixel.1 += y;
+                }
+                if call.z == level.z {
+                    for (i, j, color) in call.pixels {
+                        paint(&mut level.image, i, j, color, call.blend);
+                    }
+                } else {
+                    level.calls.push(call);
+                }
+            }
+        }
+    }
+
+    /**
+    Draws every layer on to the image, ordered by layer and then by z-index.
+
+    Pixels with the same layer and z-index replace what is beneath them, as if they were drawn directly on the image,
+    unless they are blended.
+    Semi-transparent pixels are blended with the layers and z-indexes beneath them.
+    The result is then placed on top of the background color, if there is one.
+    Afterwards, everything is drawn directly on to the image.
+     */
+    fn flatten(&mut self, background: Option<image::Rgba<u8>>) {
+        self.layered = false;
+        for (_, level) in std::mem::take(&mut self.layers) {
+            let (z, mut calls) = (level.z, level.calls);
+            calls.sort_by_key(|call| call.z);
+            let beneath = calls.partition_point(|call| call.z < z);
+            let above = calls.split_off(beneath);
+
+            self.composite(calls);
+            for (base, top) in self.img.pixels_mut().zip(level.image.pixels()) {
+                over(base, top);
+            }
+            self.composite(above);
+        }
+
+        if let Some(color) = background {
+            for pixel in self.img.pixels_mut() {
+                let mut beneath = color;
+                image::Pixel::blend(&mut beneath, pixel);
+                *pixel = beneath;
+            }
+        }
+    }
+
+    /**
+    Draws the calls on to the image, one z-index at a time, in the order they are given.
+     */
+    fn composite(&mut self, calls: Vec<DrawCall>) {
+        let mut calls = calls.into_iter().peekable();
+        while let Some(first) = calls.next() {
+            let z = first.z;
+            let mut group = vec![first];
+            while let Some(call) = calls.next_if(|call| call.z == z) {
+                group.push(call);
+            }
+
+            let mut drawn = std::collections::HashMap::new();
+            for call in group {
+                for (x, y, color) in call.pixels {
+                    let pixel = drawn.entry((x, y)).or_insert(image::Rgba([0; 4]));
+                    if call.blend {
+                        image::Pixel::blend(pixel, &color);
+                    } else {
+                        *pixel = color;
+                    }
+                }
+            }
+            for ((x, y), pixel) in drawn {
+                over(self.img.get_pixel_mut(x, y), &pixel);
+            }
+        }
+    }
+
+    /**
+    Returns a coordinate with the width and height of the image buffer.
+     */
+    pub fn dimensions(&self) -> Coordinate {
+        coordinate!(self.img.width(), self.img.height())
+    }
+
+    /**
+    Returns the coordinates occupied by the shape, each one once.
+
+    The shape is only rasterised the first time it is drawn at a size, after that it is reused.
+    With a border, only the outline of the shape is returned.
+     */
+    fn area(
+        &mut self,
+        shape: &Shape,
+        size: usize,
+        border: Option<u32>,
+    ) -> std::sync::Arc<Vec<Coordinate>> {
+        let custom = match *shape {
+            Shape::Custom(f) => f as usize,
+            _ => 0,
+        };
+        let key = (std::mem::discriminant(shape), custom, size, border);
+        // The cache only ever gains finished entries, so it is still valid if another thread panicked.
+        let areas = || self.areas.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(area) = areas().get(&key) {
+            return area.clone();
+        }
+
+        // Rasterised without holding the lock, so other tiles are not kept waiting.
+        let mut area = match border {
+            Some(width) => shape.outline(size, width),
+            None => shape.area(size),
+        };
+        area.sort_by_key(|c| (c.y, c.x));
+        area.dedup();
+        areas()
+            .entry(key)
+            .or_insert_with(|| std::sync::Arc::new(area))
+            .clone()
+    }
+}
+
+/**
+Draws the pixel on the image, either replacing what is there or blending with it.
+ */
+fn paint(image: &mut image::RgbaImage, x: u32, y: u32, color: image::Rgba<u8>, blend: bool) {
+    if blend {
+        image::Pixel::blend(image.get_pixel_mut(x, y), &color);
+    } else {
+        image.put_pixel(x, y, color);
+    }
+}
+
+/**
+Places the top pixel over the base, leaving the base as is where the top pixel is transparent.
+ */
+fn over(base: &mut image::Rgba<u8>, top: &image::Rgba<u8>) {
+    match (base[3], top[3]) {
+        (_, 0) => (),
+        (0, _) | (_, 255) => *base = *top,
+        _ => image::Pixel::blend(base, top),
+    }
+}
+
+// ------------------------------------------------------------------
+
+impl Find for HL {}
+
+impl Find for Node {}
+
+impl Find for Group {
+    /**
+    Recursively calls find as the group contains sets of Nodes.
+     */
+    fn find<H: Hash>(&self, hash: H) -> Option<Coordinate> {
+        let f = tools::find(hash.hash(), &self.nodes);
+        f.and_then(|x| Some(x.position()))
+    }
+}
+
+// ------------------------------------------------------------------
+
+impl MinMax for HL {
+    fn min_max(&self) -> Rect {
+        let zero = coordinate!();
+        let to = self.to.unwrap_or(zero);
+        Rect::new(self.position(), to)
+    }
+}
+
+impl MinMax for Node {
+    fn min_max(&self) -> Rect {
+        Rect::new(self.position(), self.position()).expand(self.size() as i32)
+    }
+}
+
+impl MinMax for Group {
+    /**
+    Returns the the largest and smallest x and y position found in the group.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() {
+    let mut group = Group::new_simple(0, 0);
+    group.push(node!(100, 100));
+    let rect = group.min_max();
+    assert_eq!(rect.min.x, 0);
+    assert_eq!(rect.max.x, 104);
+    # }
+    ```
+    */
+    fn min_max(&self) -> Rect {
+        self.nodes
+            .iter()
+            .fold(Rect::default(), |acc, node| acc.union(node.min_max()))
+            .translate(self.position())
+    }
+}
+
+// ------------------------------------------------------------------
 
 impl Location for HL {
     fn position(&self) -> Coordinate {
@@ -386,6 +1184,35 @@ impl Location for Group {
     fn position(&self) -> Coordinate {
         self.settings.position()
     }
+
+    /**
+    Returns the positions of the Nodes, including the position of the Group.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() {
+    let mut group = Group::new_simple(10, 10);
+    group.push(node!(5, 0));
+    assert_eq!(group.positions(), vec![Coordinate::new(5, 0)]);
+    # }
+    ```
+     */
+    fn positions(&self) -> Vec<Coordinate> {
+        let offset = self.position();
+        self.nodes.iter().map(|n| n.position() + offset).collect()
+    }
+}
+
+impl Location for Rect {
+    /**
+    The position of a Rect is its minimum corner.
+     */
+    fn position(&self) -> Coordinate {
+        self.min
+    }
 }
 
 impl Location for Coordinate {
@@ -402,16 +1229,35 @@ impl Draw for Node {
 
     It is recommended to not use this directory.
     But instead use the Map struct, which uses this trait implementation.
+
+    The shape of the Node is used if it has one, otherwise the given shape.
+    With a border, only the outline of the shape is drawn.
+    With a Sprite added to the Map, the image is drawn instead of the shape.
+    Parts of the shape outside of the image are left out.
     */
-    fn draw(&self, mut image: IW, offset: Coordinate, shape: &Shape) -> IW {
+    fn draw(&self, image: &mut IW, offset: Coordinate, shape: &Shape) {
         let s = consts::DEFAULT_LINK_SIZE / 2;
         let pos = self.geo + offset - coordinate!(s, s);
 
+        image.target(Layer::Edges, self.z);
         for link in &self.links {
-            image = link.draw(image, offset, u32::from(consts::DEFAULT_LINK_SIZE));
+            link.draw(image, offset, u32::from(consts::DEFAULT_LINK_SIZE), shape);
+        }
+
+        image.target(Layer::Nodes, self.z);
+        if let Some(sprite) = self.sprite {
+            if let Some(source) = image.sprites.get(&sprite.hash).cloned() {
+                let drawn = sprite.draw(image, &source, pos);
+                image.hit(self.hash, drawn);
+                return;
+            }
         }
 
-        for o in shape.area(self.size() as usize) {
+        let shape = self.shape.as_ref().unwrap_or(shape);
+        let area = image.area(shape, self.size() as usize, self.border);
+        let dim = image.dimensions();
+        let mut drawn: Option<Rect> = None;
+        for &o in area.iter() {
             let color = if o.x == 0 || o.y == 0 {
                 let c = self
                     .color
@@ -423,9 +1269,13 @@ impl Draw for Node {
             } else {
                 self.color
             };
-            image.put(&(pos + o), color);
+            let c = pos + o;
+            if c.x >= 0 && c.y >= 0 && c.x < dim.x && c.y < dim.y {
+                image.put(&c, color);
+                drawn = Some(drawn.map_or(Rect::new(c, c), |r| r.union(Rect::new(c, c))));
+            }
         }
-        image
+        image.hit(self.hash, drawn);
     }
 
     fn size(&self) -> u32 {
@@ -436,6 +1286,30 @@ impl Draw for Node {
     fn links(&self) -> &[HL] {
         &self.links
     }
+
+    /**
+    Returns the area covered by the Node and its edges.
+
+    The area is not known for Nodes drawn as Sprites, or with orthogonal edges which are routed around other elements.
+     */
+    fn bounds(&self, shape: &Shape) -> Option<Rect> {
+        if self.sprite.is_some() {
+            return None;
+        }
+        let s = consts::DEFAULT_LINK_SIZE / 2;
+        let pos = self.geo - coordinate!(s, s);
+        let size = self.size() as usize;
+        let body = match self.shape.as_ref().unwrap_or(shape) {
+            Shape::Custom(f) => f(size).into_iter().fold(Rect::new(pos, pos), |r, c| {
+                r.union(Rect::new(pos + c, pos + c))
+            }),
+            _ => Rect::new(pos, pos).expand(size as i32),
+        };
+        self.links
+            .iter()
+            .filter(|l| l.is_connected())
+            .try_fold(body, |r, l| l.bounds().map(|b| r.union(b)))
+    }
 }
 
 impl Draw for Group {
@@ -443,12 +1317,15 @@ impl Draw for Group {
     Draws the Nodes inside that Group.
 
     If none the Group is draw as blank.
+    The z-index of the Group is added to the z-index of its Nodes.
      */
-    fn draw(&self, image: IW, mut offset: Coordinate, shape: &Shape) -> IW {
+    fn draw(&self, image: &mut IW, mut offset: Coordinate, shape: &Shape) {
         offset += self.position();
-        self.nodes
-            .iter()
-            .fold(image, |acc, node| node.draw(acc, offset, shape))
+        for node in &self.nodes {
+            let mut node = *node;
+            node.z += self.settings.z;
+            node.draw(image, offset, shape);
+        }
     }
 
     fn size(&self) -> u32 {
@@ -465,6 +1342,15 @@ impl Draw for Group {
     fn links(&self) -> &[HL] {
         &self.settings.links()
     }
+
+    fn bounds(&self, shape: &Shape) -> Option<Rect> {
+        let position = self.position();
+        self.nodes
+            .iter()
+            .try_fold(Rect::new(position, position), |r, n| {
+                n.bounds(shape).map(|b| r.union(b.translate(position)))
+            })
+    }
 }
 
 // ------------------------------------------------------------------
@@ -499,6 +1385,24 @@ impl From<Node> for Group {
     }
 }
 
+impl From<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> for IW {
+    fn from(img: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) -> Self {
+        IW {
+            img,
+            obstacles: std::sync::Arc::new(Obstacles::new()),
+            origin: Coordinate::default(),
+            areas: Areas::default(),
+            sprites: std::collections::HashMap::new(),
+            layers: std::collections::BTreeMap::new(),
+            layered: false,
+            target: (Layer::Nodes, 0),
+            forced: None,
+            hits: Vec::new(),
+            drawing: (0, 0),
+        }
+    }
+}
+
 impl From<Node> for Coordinate {
     fn from(node: Node) -> Self {
         node.position()
@@ -547,7 +1451,7 @@ impl Coordinate {
     # }
     ```
      */
-    pub fn new(x: i16, y: i16) -> Self {
+    pub fn new(x: i32, y: i32) -> Self {
         Coordinate { x, y }
     }
 
@@ -563,7 +1467,7 @@ impl Coordinate {
     assert!(c.lt(11));
     ```
      */
-    pub fn lt(self, lt: i16) -> bool {
+    pub fn lt(self, lt: i32) -> bool {
         self.x < lt || self.y < lt
     }
 
@@ -586,127 +1490,500 @@ impl Coordinate {
     /**
     Creates a list of coordinates from a list of tuples with x and y positions.
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Coordinate> {
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Coordinate> {
         coordinate::from_list(&list, &|c, _i| c)
     }
-}
 
-impl Node {
     /**
-    Constructs a Node struct.
+    Compares the sum of the x and y values, instead of ordering by x and then y.
 
-    The name is converted from a &str to a hash.
+
+    ## Examples
+
+    ```
+    # use pathtracer::Coordinate;
+    # use std::cmp::Ordering;
+    let a = Coordinate::new(0, 10);
+    let b = Coordinate::new(10, 0);
+    assert_eq!(a.cmp_sum(&b), Ordering::Equal);
+    ```
      */
-    pub fn new(name: &str, geo: Coordinate) -> Self {
-        Node {
-            hash: data::calculate_hash(&name),
-            geo,
-            color: consts::DEFAULT_RGBA,
-            radius: None,
-            links: [HL::new(0, 0); consts::MAX_LINKS],
-        }
+    pub fn cmp_sum(&self, other: &Coordinate) -> std::cmp::Ordering {
+        (self.x + self.y).cmp(&(other.x + other.y))
     }
 
     /**
-    Retrive coordinate from a csv format.
+    Returns the dot product of the two Coordinates as vectors.
 
-    ## Examples
 
-    Example file format:
+    ## Examples
 
-    100,20
+    ```
+    # use pathtracer::Coordinate;
+    let a = Coordinate::new(1, 2);
+    let b = Coordinate::new(3, 4);
+    assert_eq!(a.dot(b), 11);
+    ```
+     */
+    pub fn dot(self, other: Coordinate) -> i64 {
+        i64::from(self.x) * i64::from(other.x) + i64::from(self.y) * i64::from(other.y)
+    }
 
-    40,60
+    /**
+    Returns the z value of the cross product of the two Coordinates as vectors.
 
-    30,30
+    It is positive if other is clockwise from self, as seen on the image where y grows downwards.
 
 
-    Would be equivalent to the following.
+    ## Examples
 
     ```
-    # use pathtracer::Node;
-    let list = [(100, 20), (40, 60), (30, 30)];
-    let nodes = Node::from_list(&list);
-    assert_eq!(nodes.len(), 3);
+    # use pathtracer::Coordinate;
+    let a = Coordinate::new(1, 0);
+    let b = Coordinate::new(0, 1);
+    assert_eq!(a.cross(b), 1);
+    assert_eq!(b.cross(a), -1);
     ```
      */
-    pub fn from_file(path: &str) -> Result<Vec<Self>, std::io::Error> {
-        node::from_file(path)
+    pub fn cross(self, other: Coordinate) -> i64 {
+        i64::from(self.x) * i64::from(other.y) - i64::from(self.y) * i64::from(other.x)
     }
 
     /**
-    Gets the center position of the node accounting for size.
+    Returns the length of the Coordinate as a vector from 0, 0.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Coordinate;
+    let c = Coordinate::new(3, 4);
+    assert_eq!(c.length(), 5.0);
+    ```
      */
-    pub fn center(&self) -> Coordinate {
-        let half = coordinate!(self.size() / 2, self.size() / 2);
-        self.position() + half
+    pub fn length(self) -> f64 {
+        pythagoras::theorem(self.x, self.y)
     }
 
     /**
-    Converts a list of tuples (x,y) to a Vector of Nodes.
+    Returns the unit vector pointing in the same direction, as (x, y).
 
-    Names are assigned from "A" and upwards automatically.
+    The zero Coordinate has no direction and returns (0.0, 0.0).
 
 
     ## Examples
 
-    Create three nodes from a list.
-
-    ```
-    # use pathtracer::Node;
-    let list = [(0, 0), (10, 10), (15, 15)];
-    let nodes = Node::from_list(&list);
-    assert_eq!(nodes.len(), 3);
-    ```
-
-    Returns an empty array if given an empty list.
-
     ```
-    # use pathtracer::Node;
-    let nodes = Node::from_list(&[]);
-    assert_eq!(nodes.len(), 0);
+    # use pathtracer::Coordinate;
+    let c = Coordinate::new(0, -10);
+    assert_eq!(c.normalize(), (0.0, -1.0));
     ```
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Self> {
-        coordinate::from_list(&list, &|c, i| {
-            Node::new(&std::char::from_u32(65 + i as u32).unwrap().to_string(), c)
-        })
+    pub fn normalize(self) -> (f64, f64) {
+        let length = self.length();
+        if length == 0.0 {
+            return (0.0, 0.0);
+        }
+        (f64::from(self.x) / length, f64::from(self.y) / length)
     }
 
     /**
-    Looks through all connected Nodes and returns if they are connected.
+    Multiplies both x and y by a scalar, rounding to the closest Coordinate.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Coordinate;
+    let c = Coordinate::new(10, -4);
+    assert_eq!(c.scale(1.5), Coordinate::new(15, -6));
+    ```
      */
-    pub fn is_directly_connected<P: Hash + Location>(&self, other: &P) -> bool {
-        tools::find(other.hash(), self.links()).is_some()
+    pub fn scale(self, scalar: f64) -> Coordinate {
+        Coordinate::new(
+            (f64::from(self.x) * scalar).round() as i32,
+            (f64::from(self.y) * scalar).round() as i32,
+        )
     }
 
     /**
-    Links a list of nodes together in the order they are indexed.
+    Linearly interpolates between self and other.
 
-
-    A list of A, B, C.
-    Will result in them being linked as: A -> B -> C.
+    t of 0.0 returns self, and t of 1.0 returns other.
 
 
     ## Examples
 
     ```
-    # use pathtracer::Node;
-    let nodes = Node::from_list(&[(0, 0), (20, 20)]);
-    let linked_list = Node::linked_list(nodes);
+    # use pathtracer::Coordinate;
+    let a = Coordinate::new(0, 0);
+    let b = Coordinate::new(100, 50);
+    assert_eq!(a.lerp(b, 0.5), Coordinate::new(50, 25));
     ```
      */
-    pub fn linked_list(list: Vec<Node>) -> Vec<Self> {
-        Node::linked_list_predicate(list, &|_, _| true)
+    pub fn lerp(self, other: Coordinate, t: f64) -> Coordinate {
+        self + (other - self).scale(t)
     }
 
     /**
-    Returns a specific link if it exists. Returns none if not.
+    Returns the angle in degrees to rotate self by to point in the same direction as other.
+
+    Positive angles are counter-clockwise as seen on the image, matching Coordinate::rotate.
 
 
     ## Examples
 
-    If the Node has not been linked.
+    ```
+    # use pathtracer::Coordinate;
+    let a = Coordinate::new(0, 100);
+    let b = Coordinate::new(100, 0);
+    assert_eq!(a.angle_to(b), 90.0);
+    assert_eq!(a.rotate(a.angle_to(b)), b);
+    ```
+     */
+    pub fn angle_to(self, other: Coordinate) -> f64 {
+        (-self.cross(other) as f64)
+            .atan2(self.dot(other) as f64)
+            .to_degrees()
+    }
+
+    /**
+    Rotates the Coordinate as a vector around 0, 0 by the given degrees.
+
+    Positive angles are counter-clockwise as seen on the image, where y grows downwards.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Coordinate;
+    let c = Coordinate::new(0, 100);
+    assert_eq!(c.rotate(90.0), Coordinate::new(100, 0));
+    assert_eq!(c.rotate(180.0), Coordinate::new(0, -100));
+    ```
+     */
+    pub fn rotate(self, deg: f64) -> Coordinate {
+        let (sin, cos) = deg.to_radians().sin_cos();
+        let x = f64::from(self.x);
+        let y = f64::from(self.y);
+        Coordinate::new(
+            (x * cos + y * sin).round() as i32,
+            (y * cos - x * sin).round() as i32,
+        )
+    }
+}
+
+impl Rect {
+    /**
+    Constructs a Rect covering both Coordinates, in any order.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let rect = Rect::new(Coordinate::new(10, -5), Coordinate::new(-10, 5));
+    assert_eq!(rect.min, Coordinate::new(-10, -5));
+    assert_eq!(rect.max, Coordinate::new(10, 5));
+    ```
+     */
+    pub fn new(a: Coordinate, b: Coordinate) -> Self {
+        Rect {
+            min: coordinate!(std::cmp::min(a.x, b.x), std::cmp::min(a.y, b.y)),
+            max: coordinate!(std::cmp::max(a.x, b.x), std::cmp::max(a.y, b.y)),
+        }
+    }
+
+    /**
+    Returns the distance between the minimum and maximum x.
+     */
+    pub fn width(self) -> i32 {
+        self.max.x - self.min.x
+    }
+
+    /**
+    Returns the distance between the minimum and maximum y.
+     */
+    pub fn height(self) -> i32 {
+        self.max.y - self.min.y
+    }
+
+    /**
+    Returns the width and height as a Coordinate.
+     */
+    pub fn size(self) -> Coordinate {
+        self.max - self.min
+    }
+
+    /**
+    Returns the Coordinate in the middle of the Rect.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let rect = Rect::new(Coordinate::new(0, 0), Coordinate::new(100, 50));
+    assert_eq!(rect.center(), Coordinate::new(50, 25));
+    ```
+     */
+    pub fn center(self) -> Coordinate {
+        tools::midpoint(self.min, self.max)
+    }
+
+    /**
+    Returns true if the location is inside the Rect, including its edges.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let rect = Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 10));
+    assert!(rect.contains(&Coordinate::new(10, 0)));
+    assert!(!rect.contains(&Coordinate::new(11, 0)));
+    ```
+     */
+    pub fn contains<L: Location>(self, l: &L) -> bool {
+        let c = l.position();
+        c.x >= self.min.x && c.x <= self.max.x && c.y >= self.min.y && c.y <= self.max.y
+    }
+
+    /**
+    Returns true if the two Rects overlap, including touching edges.
+     */
+    pub fn intersects(self, other: Rect) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    /**
+    Returns the area both Rects cover, or None if they do not overlap.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let a = Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 10));
+    let b = Rect::new(Coordinate::new(5, 5), Coordinate::new(20, 20));
+    let c = Rect::new(Coordinate::new(50, 50), Coordinate::new(60, 60));
+    assert_eq!(a.intersection(b), Some(Rect::new(Coordinate::new(5, 5), Coordinate::new(10, 10))));
+    assert_eq!(a.intersection(c), None);
+    ```
+     */
+    pub fn intersection(self, other: Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Rect {
+            min: coordinate!(
+                std::cmp::max(self.min.x, other.min.x),
+                std::cmp::max(self.min.y, other.min.y)
+            ),
+            max: coordinate!(
+                std::cmp::min(self.max.x, other.max.x),
+                std::cmp::min(self.max.y, other.max.y)
+            ),
+        })
+    }
+
+    /**
+    Returns the smallest Rect that covers both Rects.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let a = Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 10));
+    let b = Rect::new(Coordinate::new(-5, 5), Coordinate::new(5, 20));
+    assert_eq!(a.union(b), Rect::new(Coordinate::new(-5, 0), Coordinate::new(10, 20)));
+    ```
+     */
+    pub fn union(self, other: Rect) -> Rect {
+        Rect {
+            min: coordinate!(
+                std::cmp::min(self.min.x, other.min.x),
+                std::cmp::min(self.min.y, other.min.y)
+            ),
+            max: coordinate!(
+                std::cmp::max(self.max.x, other.max.x),
+                std::cmp::max(self.max.y, other.max.y)
+            ),
+        }
+    }
+
+    /**
+    Grows the Rect by the amount in every direction. Negative amounts shrink it.
+
+    A Rect shrunk by more than half its size collapses to its center on that axis, rather than growing inside out.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let rect = Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 10));
+    assert_eq!(rect.expand(5), Rect::new(Coordinate::new(-5, -5), Coordinate::new(15, 15)));
+
+    let rect = Rect::new(Coordinate::new(0, 0), Coordinate::new(2, 2));
+    assert_eq!(rect.expand(-5), Rect::new(Coordinate::new(1, 1), Coordinate::new(1, 1)));
+
+    let rect = Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 2));
+    assert_eq!(rect.expand(-2), Rect::new(Coordinate::new(2, 1), Coordinate::new(8, 1)));
+    ```
+     */
+    pub fn expand(self, amount: i32) -> Rect {
+        let axis = |min: i32, max: i32| {
+            if i64::from(max) - i64::from(min) < -2 * i64::from(amount) {
+                let center = min + (max - min) / 2;
+                (center, center)
+            } else {
+                (min - amount, max + amount)
+            }
+        };
+        let (min_x, max_x) = axis(self.min.x, self.max.x);
+        let (min_y, max_y) = axis(self.min.y, self.max.y);
+        Rect {
+            min: coordinate!(min_x, min_y),
+            max: coordinate!(max_x, max_y),
+        }
+    }
+
+    /**
+    Moves the Rect by the given offset.
+     */
+    pub fn translate(self, offset: Coordinate) -> Rect {
+        Rect {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+impl Node {
+    /**
+    Constructs a Node struct.
+
+    The name is converted from a &str to a hash.
+     */
+    pub fn new(name: &str, geo: Coordinate) -> Self {
+        Node {
+            hash: data::calculate_hash(&name),
+            geo,
+            color: consts::DEFAULT_RGBA,
+            radius: None,
+            shape: None,
+            border: None,
+            sprite: None,
+            z: 0,
+            links: [HL::new(0, 0); consts::MAX_LINKS],
+        }
+    }
+
+    /**
+    Retrive coordinate from a csv format.
+
+    ## Examples
+
+    Example file format:
+
+    100,20
+
+    40,60
+
+    30,30
+
+
+    Would be equivalent to the following.
+
+    ```
+    # use pathtracer::Node;
+    let list = [(100, 20), (40, 60), (30, 30)];
+    let nodes = Node::from_list(&list);
+    assert_eq!(nodes.len(), 3);
+    ```
+     */
+    pub fn from_file(path: &str) -> Result<Vec<Self>, std::io::Error> {
+        node::from_file(path)
+    }
+
+    /**
+    Gets the center position of the node accounting for size.
+     */
+    pub fn center(&self) -> Coordinate {
+        let half = coordinate!(self.size() / 2, self.size() / 2);
+        self.position() + half
+    }
+
+    /**
+    Converts a list of tuples (x,y) to a Vector of Nodes.
+
+    Names are assigned from "A" and upwards automatically.
+
+
+    ## Examples
+
+    Create three nodes from a list.
+
+    ```
+    # use pathtracer::Node;
+    let list = [(0, 0), (10, 10), (15, 15)];
+    let nodes = Node::from_list(&list);
+    assert_eq!(nodes.len(), 3);
+    ```
+
+    Returns an empty array if given an empty list.
+
+    ```
+    # use pathtracer::Node;
+    let nodes = Node::from_list(&[]);
+    assert_eq!(nodes.len(), 0);
+    ```
+     */
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Self> {
+        coordinate::from_list(&list, &|c, i| {
+            Node::new(&std::char::from_u32(65 + i as u32).unwrap().to_string(), c)
+        })
+    }
+
+    /**
+    Looks through all connected Nodes and returns if they are connected.
+     */
+    pub fn is_directly_connected<P: Hash + Location>(&self, other: &P) -> bool {
+        tools::find(other.hash(), self.links()).is_some()
+    }
+
+    /**
+    Links a list of nodes together in the order they are indexed.
+
+
+    A list of A, B, C.
+    Will result in them being linked as: A -> B -> C.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Node;
+    let nodes = Node::from_list(&[(0, 0), (20, 20)]);
+    let linked_list = Node::linked_list(nodes);
+    ```
+     */
+    pub fn linked_list(list: Vec<Node>) -> Vec<Self> {
+        Node::linked_list_predicate(list, &|_, _| true)
+    }
+
+    /**
+    Returns a specific link if it exists. Returns none if not.
+
+
+    ## Examples
+
+    If the Node has not been linked.
 
     ```
     # #[macro_use] extern crate pathtracer;
@@ -969,6 +2246,9 @@ impl Node {
         let i = self.get_link_avail_index();
         self.links[i] = HL {
             style: EdgeStyle::default(),
+            arrow: Arrow::default(),
+            stroke: Stroke::default(),
+            color: None,
             f: self.hash,
             t: other.hash(),
             from: Some(self.geo),
@@ -981,6 +2261,9 @@ impl HL {
     pub fn new(f: u64, t: u64) -> Self {
         HL {
             style: EdgeStyle::default(),
+            arrow: Arrow::default(),
+            stroke: Stroke::default(),
+            color: None,
             f,
             t,
             from: None,
@@ -1036,44 +2319,397 @@ impl HL {
     }
 
     /**
-    Draws the HL on an Image Wrapper.
+    Returns the Coordinates the edge passes through, ordered from the start to the end of the edge.
 
-    Will not draw the Edge if it is not connected, or if the the HL's from and to connections are the same Node.
+    The Coordinates follow the EdgeStyle of the HL.
 
-    Size increases drawing time with a squared factor.
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let b = Node::new("B", Coordinate::new(0, 0));
+    let mut a = Node::new("A", Coordinate::new(10, 0));
+    a.link(&b);
+    let plot = a.hl(0).unwrap().plot();
+    assert_eq!(plot.first(), Some(&Coordinate::new(10, 0)));
+    assert_eq!(plot.last(), Some(&Coordinate::new(0, 0)));
+    ```
      */
-    fn draw(&self, mut image: IW, mut offset: Coordinate, size: u32) -> IW {
-        let (mut from, mut to) = self.min_max();
-        if !self.is_connected() || from == to {
-            return image;
-        }
-        let s = coordinate!(size / 2);
-        offset += s;
-        from += offset;
-        to += offset;
+    pub fn plot(&self) -> Vec<Coordinate> {
+        self.plot_ordered(self.position(), self.to.unwrap_or_default(), &[])
+    }
 
-        for i in 0..size {
-            for j in 0..size {
-                let add = coordinate!(j, i) - s - s;
-                let col = (size - i) as u8 * consts::DEFAULT_SHADE as u8;
-                let plot = match self.style {
-                    EdgeStyle::Direct => {
-                        tools::plot_type(from + add, to + add, &tools::plot_bresenham)
-                    }
-                    EdgeStyle::Straight => {
-                        tools::plot_type(from + add, to + add, &tools::plot_rectangle)
-                    }
-                    EdgeStyle::Ellipse => {
-                        tools::plot_type(from + add, to + add, &tools::plot_ellipse)
-                    }
-                };
-                let _ = plot
+    /**
+    Sets the markers showing the direction of the edge.
+
+    Check out the Arrow struct for all alternatives.
+     */
+    pub fn arrow(&mut self, arrow: Arrow) {
+        self.arrow = arrow;
+    }
+
+    /**
+    Sets how the line of the edge is drawn.
+
+    Check out the Stroke enum for all alternatives.
+     */
+    pub fn stroke(&mut self, stroke: Stroke) {
+        self.stroke = stroke;
+    }
+
+    /**
+    Sets the color of the edge and its markers, replacing the grey shading edges are drawn with by default.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    # fn main() -> std::io::Result<()> {
+    let b = Node::new("B", Coordinate::new(0, 0));
+    let mut a = Node::new("A", Coordinate::new(10, 0));
+    a.link(&b);
+    a.hl_mut(0)?.color(image::Rgba([200, 0, 0, 255]));
+    assert_eq!(a.hl(0)?.color, Some(image::Rgba([200, 0, 0, 255])));
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn color(&mut self, color: image::Rgba<u8>) {
+        self.color = Some(color);
+    }
+
+    /**
+    Returns the area the edge and its markers are drawn on, or None for orthogonal edges.
+     */
+    fn bounds(&self) -> Option<Rect> {
+        if let EdgeStyle::Orthogonal = self.style {
+            return None;
+        }
+        let from = self.position();
+        let rect = self
+            .plot()
+            .into_iter()
+            .fold(Rect::new(from, from), |r, c| r.union(Rect::new(c, c)));
+        let marker = std::cmp::max(self.arrow.head, u32::from(consts::DEFAULT_ARROW_SIZE));
+        Some(rect.expand((marker + u32::from(consts::DEFAULT_LINK_SIZE)) as i32))
+    }
+
+    /**
+    Plots the Coordinates between two points, ordered from a to b.
+     */
+    fn plot_ordered(&self, a: Coordinate, b: Coordinate, obstacles: &[Rect]) -> Vec<Coordinate> {
+        let mut plot = self.plot_between(a, b, obstacles);
+        let distance = |c: Option<&Coordinate>| c.map(|&c| (c - a).dot(c - a));
+        if distance(plot.first()) > distance(plot.last()) {
+            plot.reverse();
+        }
+        plot
+    }
+
+    /**
+    Plots the Coordinates between two points using the EdgeStyle of the HL.
+
+    Only orthogonal edges are routed around the obstacles.
+     */
+    fn plot_between(&self, a: Coordinate, b: Coordinate, obstacles: &[Rect]) -> Vec<Coordinate> {
+        let side = |along: f64, bend: i32| {
+            let d = b - a;
+            a.lerp(b, along) + coordinate!(-d.y, d.x).scale(f64::from(bend) / 100.0)
+        };
+        match self.style {
+            EdgeStyle::Direct => tools::plot_type(a, b, &tools::plot_bresenham),
+            EdgeStyle::Straight => tools::plot_type(a, b, &tools::plot_rectangle),
+            EdgeStyle::Ellipse => tools::plot_type(a, b, &tools::plot_ellipse),
+            EdgeStyle::Quadratic(bend) => tools::plot_quadratic(a, side(0.5, bend), b),
+            EdgeStyle::Cubic(bend) => {
+                tools::plot_cubic(a, side(1.0 / 3.0, bend), side(2.0 / 3.0, -bend), b)
+            }
+            EdgeStyle::CatmullRom(via) => {
+                tools::plot_catmull_rom(&[a, tools::midpoint(a, b) + via, b])
+            }
+            EdgeStyle::Orthogonal => tools::plot_orthogonal(a, b, obstacles),
+            EdgeStyle::Bundled(strength) => tools::plot_bundled(a, b, strength),
+        }
+    }
+
+    /**
+    Draws the HL on an Image Wrapper.
+
+    Will not draw the Edge if it is not connected, or if the the HL's from and to connections are the same Node.
+
+    Curved and routed edges can go outside of the image, the parts outside are left out.
+
+    Size increases drawing time with a squared factor.
+     */
+    fn draw(&self, image: &mut IW, mut offset: Coordinate, size: u32, shape: &Shape) {
+        let from = self.position();
+        let to = self.to.unwrap_or_default();
+        if !self.is_connected() || from == to {
+            return;
+        }
+        let s = coordinate!(size / 2);
+        offset += s;
+
+        // The edge is plotted before the offset is added, so that it is plotted the same as by HL::plot.
+        let obstacles = match self.style {
+            EdgeStyle::Orthogonal => {
+                let shift = s - offset - image.origin;
+                let area = Rect::new(from, to).expand(consts::ROUTE_MARGIN);
+                image
+                    .obstacles
+                    .near(area.translate(coordinate!() - shift))
+                    .into_iter()
+                    .map(|r| r.translate(shift))
+                    .collect::<Vec<_>>()
+            }
+            _ => Vec::new(),
+        };
+        let mut plot = self
+            .plot_ordered(from, to, &obstacles)
+            .into_iter()
+            .map(|c| c + offset)
+            .collect::<Vec<_>>();
+        let to = to + offset;
+        if self.arrow.clip > 0 {
+            // Compares the center of the line with the area of the Node, which is drawn from the same corner.
+            let shape = self.arrow.shape.as_ref().unwrap_or(shape);
+            let area = image
+                .area(shape, self.arrow.clip as usize, None)
+                .iter()
+                .map(|c| (c.x, c.y))
+                .collect::<std::collections::HashSet<_>>();
+            let inside = |c: Coordinate| {
+                let c = c - to + s;
+                area.contains(&(c.x, c.y))
+            };
+            while plot.last().is_some_and(|&c| inside(c)) {
+                plot.pop();
+            }
+        }
+
+        let dim = image.dimensions();
+        for i in 0..size {
+            for j in 0..size {
+                let add = coordinate!(j, i) - s - s;
+                let col = (size - i) as u8 * consts::DEFAULT_SHADE as u8;
+                let color = self
+                    .color
+                    .unwrap_or_else(|| image::Rgba([col, col, col, u8::max_value()]));
+                let drawn = plot
                     .iter()
-                    .map(|c| image.put(c, image::Rgba([col, col, col, u8::max_value()])))
-                    .collect::<Vec<_>>();
+                    .enumerate()
+                    .filter(|(k, _)| self.stroke.is_drawn(*k))
+                    .map(|(_, &c)| c + add);
+                for c in drawn {
+                    if c.x >= 0 && c.y >= 0 && c.x < dim.x && c.y < dim.y {
+                        image.put(&c, color);
+                    }
+                }
+            }
+        }
+
+        self.draw_arrow(image, &plot, s);
+    }
+
+    /**
+    Draws the arrowhead at the end of the plot and the chevrons along it.
+
+    Markers are left out where they do not fit on the image.
+     */
+    fn draw_arrow(&self, image: &mut IW, plot: &[Coordinate], s: Coordinate) {
+        let arrow = self.arrow;
+        if plot.len() < 2 || (arrow.head == 0 && arrow.chevrons == 0) {
+            return;
+        }
+
+        let last = plot.len() - 1;
+        let back = |i: usize, size: u32| plot[i.saturating_sub(size as usize)];
+        let mut marks = Vec::new();
+        if arrow.head > 0 {
+            marks.append(&mut tools::plot_arrowhead(
+                plot[last],
+                back(last, arrow.head),
+                arrow.head,
+                !arrow.open,
+            ));
+        }
+
+        let size = if arrow.head > 0 {
+            arrow.head
+        } else {
+            u32::from(consts::DEFAULT_ARROW_SIZE)
+        };
+        for k in 1..=arrow.chevrons as usize {
+            let i = last * k / (arrow.chevrons as usize + 1);
+            marks.append(&mut tools::plot_arrowhead(
+                plot[i],
+                back(i, size),
+                size,
+                false,
+            ));
+        }
+
+        let dim = image.dimensions();
+        let shade = consts::DEFAULT_SHADE as u8;
+        let color = self
+            .color
+            .unwrap_or_else(|| image::Rgba([shade, shade, shade, 255]));
+        for c in marks.into_iter().map(|c| c - s) {
+            if c.x >= 0 && c.y >= 0 && c.x < dim.x && c.y < dim.y {
+                image.put(&c, color);
             }
         }
-        image
+    }
+}
+
+impl Sprite {
+    /**
+    Constructs a Sprite referring to the image with the given name, drawn at its original size.
+     */
+    pub fn new(name: &str) -> Self {
+        Sprite {
+            hash: data::calculate_hash(&name),
+            scale: 1.0,
+        }
+    }
+
+    /**
+    Sets how much the image is scaled when drawn, 0.5 draws it at half its width and height.
+     */
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /**
+    Draws the image centered at the position, blending it with what is already drawn there.
+
+    Scaling picks the nearest pixel of the image, and parts outside of the canvas are left out.
+    Returns the pixels drawn on, None if the Sprite is entirely outside of the canvas.
+     */
+    fn draw(&self, image: &mut IW, sprite: &image::RgbaImage, center: Coordinate) -> Option<Rect> {
+        let scale = |v: u32| (f64::from(v) * self.scale).round() as i32;
+        let size = coordinate!(scale(sprite.width()), scale(sprite.height()));
+        if size.x <= 0 || size.y <= 0 {
+            return None;
+        }
+        let min = center - coordinate!(size.x / 2, size.y / 2);
+        let dim = image.dimensions();
+        let from = coordinate!(std::cmp::max(min.x, 0), std::cmp::max(min.y, 0));
+        let to = coordinate!(
+            std::cmp::min(min.x + size.x, dim.x),
+            std::cmp::min(min.y + size.y, dim.y)
+        );
+        if from.x >= to.x || from.y >= to.y {
+            return None;
+        }
+        for y in from.y..to.y {
+            for x in from.x..to.x {
+                let source =
+                    |v: i32, max: u32| std::cmp::min((f64::from(v) / self.scale) as u32, max - 1);
+                let color = sprite.get_pixel(
+                    source(x - min.x, sprite.width()),
+                    source(y - min.y, sprite.height()),
+                );
+                image.blend(x as u32, y as u32, *color);
+            }
+        }
+        Some(Rect::new(from, to - coordinate!(1, 1)))
+    }
+}
+
+impl Stroke {
+    /**
+    Returns true if the step along the edge is drawn.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Stroke;
+    let dashed = Stroke::Dashed(2, 1);
+    let drawn = (0..6).map(|i| dashed.is_drawn(i)).collect::<Vec<_>>();
+    assert_eq!(drawn, vec![true, true, false, true, true, false]);
+    assert!(Stroke::Pattern(0b101).is_drawn(2));
+    assert!(!Stroke::Pattern(0b101).is_drawn(33));
+    ```
+     */
+    pub fn is_drawn(&self, step: usize) -> bool {
+        match *self {
+            Stroke::Solid => true,
+            Stroke::Dashed(dash, gap) => {
+                let period = dash as usize + gap as usize;
+                period == 0 || step % period < dash as usize
+            }
+            Stroke::Dotted(spacing) => Stroke::Dashed(1, spacing.saturating_sub(1)).is_drawn(step),
+            Stroke::Pattern(bits) => (bits >> (step % 32)) & 1 == 1,
+        }
+    }
+}
+
+impl Arrow {
+    /**
+    Constructs an Arrow without any markers.
+     */
+    pub fn new() -> Self {
+        Arrow::default()
+    }
+
+    /**
+    Draws an arrowhead of the given length at the end of the edge, 0 draws none.
+     */
+    pub fn head(mut self, size: u32) -> Self {
+        self.head = size;
+        self
+    }
+
+    /**
+    Draws the arrowhead as an outline instead of filled.
+     */
+    pub fn open(mut self) -> Self {
+        self.open = true;
+        self
+    }
+
+    /**
+    Draws a number of chevrons, evenly spaced along the edge, pointing the way it goes.
+     */
+    pub fn chevrons(mut self, chevrons: u32) -> Self {
+        self.chevrons = chevrons;
+        self
+    }
+
+    /**
+    Ends the edge at the boundary of the Node it links to, given the size of that Node.
+
+    The boundary follows the shape the Nodes are mapped with, use Arrow::clip_to for Nodes with a shape of their own.
+     */
+    pub fn clip(mut self, size: u32) -> Self {
+        self.clip = size;
+        self
+    }
+
+    /**
+    Ends the edge at the boundary of the Node it links to, following the size and shape of that Node.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut b = Node::new("B", Coordinate::new(0, 0));
+    b.shape = Some(Shape::Star);
+    b.radius = Some(12);
+    let arrow = Arrow::new().head(6).clip_to(&b);
+    assert_eq!((arrow.clip, arrow.shape), (12, Some(Shape::Star)));
+    ```
+     */
+    pub fn clip_to(mut self, node: &Node) -> Self {
+        self.clip = node.size();
+        self.shape = node.shape;
+        self
     }
 }
 
@@ -1342,7 +2978,7 @@ impl Group {
     # }
     ```
      */
-    pub fn new_simple(x: i16, y: i16) -> Self {
+    pub fn new_simple(x: i32, y: i32) -> Self {
         Group::new(&(x + y).to_string(), Coordinate::new(x, y))
     }
 
@@ -1408,7 +3044,7 @@ impl Group {
      */
     pub fn gen_color(&self, coordinates: Coordinate) -> image::Rgba<u8> {
         tools::range_color(
-            self.dynamic_radius() as i16,
+            self.dynamic_radius() as i32,
             self.settings.color,
             self.settings.geo,
             coordinates,
@@ -1429,12 +3065,44 @@ impl Group {
     assert_eq!(groups.len(), 3);
     ```
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Self> {
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Self> {
         coordinate::from_list(&list, &|c, i| {
             Group::new(&std::char::from_u32(65 + i as u32).unwrap().to_string(), c)
         })
     }
 
+    /**
+    Builds a spatial index over the nodes in the group, positioned absolutely rather than relative to the group.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() {
+    let mut group = cluster!(100, 100);
+    group.push(node!(110, 100));
+    group.push(node!(150, 100));
+    let index = group.index();
+    let nearest = index.nearest(coordinate!(112, 100), 1);
+    assert_eq!(nearest[0].geo, coordinate!(110, 100));
+    # }
+    ```
+     */
+    pub fn index(&self) -> spatial::QuadTree<Node> {
+        let offset = self.position();
+        spatial::QuadTree::new(
+            self.nodes
+                .iter()
+                .map(|node| {
+                    let mut node = *node;
+                    node.geo += offset;
+                    node
+                })
+                .collect(),
+        )
+    }
+
     /**
     Link together groups.
 
@@ -1463,6 +3131,27 @@ impl<T: Draw + Hash + std::marker::Copy> Network<T> {
 
         Network { hash_map }
     }
+
+    /**
+    Builds a spatial index over the elements in the network.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::from_list(&[(0, 0), (10, 10), (20, 20), (30, 30)]);
+    let network = Network::new(nodes);
+    let index = network.index();
+    assert_eq!(index.within_radius(Coordinate::new(0, 0), 15).len(), 2);
+    ```
+     */
+    pub fn index(&self) -> spatial::QuadTree<T>
+    where
+        T: Location,
+    {
+        spatial::QuadTree::new(self.hash_map.iter().filter_map(|x| *x).collect())
+    }
 }
 
 // ------------------------------------------------------------------
@@ -1472,12 +3161,131 @@ impl Map {
     Creates a new map, no min_max are intially required and are generated automatically when calling Map::map.
      */
     pub fn new() -> Self {
+        Map::default()
+    }
+
+    /**
+    Creates a map with a canvas covering the area of a geographic Viewport.
+
+    Elements are positioned as is, use the Viewport to convert latitude and longitude to Coordinates.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{geo::*, *};
+    let viewport = Viewport::world(Projection::Mercator, 256);
+    let nodes = vec![viewport.node("Stockholm", LatLon::new(59.33, 18.07))];
+    let image = Map::new_geo(&viewport).map(&nodes).consume();
+    assert_eq!(image.dimensions(), Coordinate::new(256, 256));
+    ```
+     */
+    pub fn new_geo(viewport: &geo::Viewport) -> Self {
+        Map::new_background(map::gen_canvas(viewport.width, viewport.height))
+    }
+
+    /**
+    Creates a map which draws on top of an existing image, instead of a blank canvas.
+
+    Elements are positioned as is, so a Coordinate is the pixel it is drawn at.
+    Parts of elements outside of the image are left out.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let background = image::RgbaImage::from_pixel(50, 50, image::Rgba([240, 240, 220, 255]));
+    let nodes = Node::linked_list(Node::from_list(&[(10, 10), (40, 30)]));
+    let image = Map::new_background(background).map(&nodes).consume();
+    assert_eq!(image.image().get_pixel(0, 0), &image::Rgba([240, 240, 220, 255]));
+    ```
+     */
+    pub fn new_background(image: image::RgbaImage) -> Self {
         Map {
-            image: None,
-            add: coordinate!(),
+            image: Some(IW::layered(image)),
+            ..Map::default()
         }
     }
 
+    /**
+    Creates a map with a blank canvas of the given size, instead of one sized to fit the elements mapped first.
+
+    Check out Map::new_background for how elements are positioned.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes: Vec<Node> = Vec::new();
+    let image = Map::new_canvas(40, 30).map(&nodes).consume();
+    assert_eq!(image.dimensions(), Coordinate::new(40, 30));
+    ```
+     */
+    pub fn new_canvas(width: u32, height: u32) -> Self {
+        Map::new_background(map::gen_canvas(width, height))
+    }
+
+    /**
+    Sets the color beneath everything drawn, instead of leaving the image transparent where nothing is drawn.
+
+    The color is applied when the image is output, so it can be set at any point.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let white = image::Rgba([255, 255, 255, 255]);
+    let nodes = Node::from_list(&[(0, 0), (40, 20)]);
+    let image = Map::new().background(white).map(&nodes).consume();
+    assert_eq!(image.image().get_pixel(0, 0), &white);
+    ```
+     */
+    pub fn background(mut self, color: image::Rgba<u8>) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /**
+    Creates a map which draws on top of an image loaded from disk.
+
+    Check out Map::new_background for how elements are positioned.
+
+
+    ## Errors
+
+    If the image can not be opened or decoded.
+     */
+    pub fn open_background(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        let image = image::open(path).map_err(|e| match e {
+            image::ImageError::IoError(e) => e,
+            e => std::io::Error::new(std::io::ErrorKind::Other, e.to_string()),
+        })?;
+        Ok(Map::new_background(image.to_rgba()))
+    }
+
+    /**
+    Adds an image that Nodes can be drawn as, by giving them a Sprite with the same name.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let icon = image::RgbaImage::from_pixel(8, 8, image::Rgba([20, 120, 200, 255]));
+    let mut node = Node::new("A", Coordinate::new(0, 0));
+    node.sprite = Some(Sprite::new("avatar").scale(0.5));
+    let image = Map::new().sprite("avatar", icon).map(&[node]).consume();
+    ```
+     */
+    pub fn sprite(mut self, name: &str, image: image::RgbaImage) -> Self {
+        self.sprites
+            .insert(data::calculate_hash(&name), std::sync::Arc::new(image));
+        self
+    }
+
     /**
     Saves the image to disk at the given Path.
 
@@ -1497,14 +3305,168 @@ impl Map {
     ```
      */
     pub fn save(self, path: &std::path::Path) -> Result<(), std::io::Error> {
-        self.image.unwrap().image().save(path)
+        self.try_consume()?.image().save(path)
+    }
+
+    /**
+    Encodes the image into the writer using the given format.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    # fn main() -> std::io::Result<()> {
+    let mut bytes = Vec::new();
+    Map::new()
+        .map(&Node::from_list(&[(0, 0), (10, 10)]))
+        .write_to(&mut bytes, image::ImageOutputFormat::PNG)?;
+    assert_eq!(&bytes[1..4], b"PNG");
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If nothing has been mapped, if the format is not supported, or if the writer fails.
+     */
+    pub fn write_to<W: std::io::Write, F: Into<image::ImageOutputFormat>>(
+        self,
+        writer: &mut W,
+        format: F,
+    ) -> Result<(), std::io::Error> {
+        let image = self.try_consume()?;
+        image::DynamicImage::ImageRgba8(image.img)
+            .write_to(writer, format)
+            .map_err(|e| match e {
+                image::ImageError::IoError(e) => e,
+                e => std::io::Error::new(std::io::ErrorKind::Other, e.to_string()),
+            })
+    }
+
+    /**
+    Returns the offset added to element positions when they are drawn on the image.
+
+    Subtracting it from a pixel position gives the position of the elements drawn there,
+    which can be looked up in a spatial index.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{spatial::QuadTree, *};
+    let nodes = Node::from_list(&[(-20, -20), (20, 20)]);
+    let map = Map::new().map(&nodes);
+    let index = QuadTree::new(nodes);
+    let pixel = Coordinate::new(46, 46);
+    let hit = index.within_radius(pixel - map.offset(), 4);
+    assert_eq!(hit[0].geo, Coordinate::new(20, 20));
+    ```
+     */
+    pub fn offset(&self) -> Coordinate {
+        self.add
+    }
+
+    /**
+    Returns where every Node has been drawn on the image, ordered the same way the Nodes are drawn on top of each other.
+
+    That is by layer, then by z-index, and then in the order they were mapped.
+     */
+    pub fn hitboxes(&self) -> &[map::hitbox::Hitbox] {
+        &self.hitboxes
+    }
+
+    /**
+    Returns the hash of the Node drawn at the pixel, the one on top if several Nodes were drawn there.
+
+    Only the area of each Node is used, so pixels next to the shape of a Node may also return it.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::from_list(&[(0, 0), (40, 20)]);
+    let map = Map::new().map(&nodes);
+    let at = nodes[1].geo + map.offset();
+    assert_eq!(map.element_at(at.x as u32, at.y as u32), Some(nodes[1].hash()));
+    assert_eq!(map.element_at(0, 0), None);
+    ```
+     */
+    pub fn element_at(&self, x: u32, y: u32) -> Option<u64> {
+        let pixel = coordinate!(x, y);
+        self.hitboxes
+            .iter()
+            .rev()
+            .find(|hitbox| hitbox.contains(pixel))
+            .map(|hitbox| hitbox.hash)
     }
 
     /**
-    Consumes the Map and returns the ImageWrapper.
+    Saves an HTML image map of the Nodes drawn, to make the image clickable when shown with usemap="#name".
+
+    Check out map::hitbox::html for the format.
+
+
+    ## Errors
+
+    If the file can not be written.
+     */
+    pub fn save_image_map(&self, path: &std::path::Path, name: &str) -> Result<(), std::io::Error> {
+        std::fs::write(path, map::hitbox::html(&self.hitboxes, name))
+    }
+
+    /**
+    Saves the Hitboxes of the Nodes drawn as JSON.
+
+    Check out map::hitbox::json for the format.
+
+
+    ## Errors
+
+    If the file can not be written.
+     */
+    pub fn save_hitboxes(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        std::fs::write(path, map::hitbox::json(&self.hitboxes))
+    }
+
+    /**
+    Consumes the Map and returns the ImageWrapper, with every layer drawn on to it.
+
+    The image is empty if nothing has been mapped, check out Map::try_consume to handle that case.
      */
     pub fn consume(self) -> IW {
-        self.image.unwrap()
+        self.try_consume()
+            .unwrap_or_else(|_| IW::from(map::gen_canvas(0, 0)))
+    }
+
+    /**
+    Consumes the Map and returns the ImageWrapper, with every layer drawn on to the background color.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    assert_eq!(Map::new().try_consume().unwrap_err(), MapError::Empty);
+    assert_eq!(Map::new_canvas(0, 10).try_consume().unwrap_err(), MapError::ZeroSized);
+    assert!(Map::new_canvas(10, 10).try_consume().is_ok());
+    ```
+
+
+    ## Errors
+
+    If nothing has been mapped and no canvas was given, or if the image has no pixels.
+     */
+    pub fn try_consume(self) -> Result<IW, MapError> {
+        let mut image = self.image.ok_or(MapError::Empty)?;
+        let (width, height) = image.img.dimensions();
+        if width == 0 || height == 0 {
+            return Err(MapError::ZeroSized);
+        }
+        image.flatten(self.background);
+        Ok(image)
     }
 
     /**
@@ -1549,41 +3511,339 @@ impl Map {
         self.map_params(&element, &|_| true, shape)
     }
 
+    /**
+    Maps the Nodes with every edge restyled by the closure.
+
+    The closure is given each edge and returns it as it should be drawn, the Nodes themselves are left unchanged.
+
+
+    ## Examples
+
+    Draws edges leaving the left half of the image dashed and red.
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (20, 10), (40, 0), (60, 10)]));
+    let image = Map::new()
+        .map_styled(&nodes, &|hl| {
+            let mut hl = *hl;
+            if hl.position().x < 30 {
+                hl.stroke(Stroke::Dashed(4, 2));
+                hl.color(image::Rgba([200, 30, 30, 255]));
+            }
+            hl
+        })
+        .consume();
+    ```
+     */
+    pub fn map_styled(self, nodes: &[Node], style: &Fn(&HL) -> HL) -> Self {
+        let nodes = nodes
+            .iter()
+            .map(|node| {
+                let mut node = *node;
+                for link in node.links.iter_mut() {
+                    *link = style(link);
+                }
+                node
+            })
+            .collect::<Vec<_>>();
+        self.map(&nodes)
+    }
+
     /**
     Maps the elements without stabalizing the positions on the canvas.
      */
     pub fn map_absolute<T: Draw + Location + Hash + MinMax>(mut self, element: &[T]) -> Self {
-        if self.image.is_none() {
-            let (image, _) = map::gen_map(&element);
-            self.image = Some(IW { img: image });
+        if self.image.is_none() && !element.is_empty() {
+            self.image = Some(IW::layered(map::gen_map(&element).0));
         }
         self.map(element)
     }
 
+    /**
+    Draws the Legend in its corner of the image, on the Overlay layer.
+
+    Nothing is drawn if nothing has been mapped, since the size of the image is not known yet.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{color::{legend::Legend, Scale}, *};
+    let scale = Scale::viridis().domain(0.0, 40.0);
+    let mut nodes = Node::from_list(&[(0, 0), (20, 0), (40, 0)]);
+    for node in nodes.iter_mut() {
+        node.color = scale.color(f64::from(node.geo.x));
+    }
+    let image = Map::new()
+        .map(&nodes)
+        .legend(&Legend::from_scale(&scale, 3))
+        .consume();
+    ```
+     */
+    pub fn legend(mut self, legend: &color::legend::Legend) -> Self {
+        if let Some(image) = self.image.as_mut() {
+            image.target(Layer::Overlay, 0);
+            legend.draw(image);
+        }
+        self
+    }
+
+    /**
+    Maps the density of the elements instead of the elements themselves.
+
+    The positions of every element are accumulated in to the cells of the Heatmap.
+    The density is drawn on the Background layer, beneath the elements no matter when they are mapped.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::heatmap::Heatmap, *};
+    let mut group = Group::new_simple(0, 0);
+    group.add(500);
+    let image = Map::new()
+        .map_density(&[group], &Heatmap::new().cell(2))
+        .consume();
+    ```
+     */
+    pub fn map_density<T: Draw + Location + Hash + MinMax>(
+        mut self,
+        element: &[T],
+        heatmap: &map::heatmap::Heatmap,
+    ) -> Self {
+        let positions = element
+            .iter()
+            .flat_map(|x| x.positions())
+            .collect::<Vec<_>>();
+        let (image, add) = match self.init(element) {
+            Some(init) => init,
+            None => return self,
+        };
+        image.target(Layer::Background, 0);
+        heatmap.draw(image, &positions, add);
+        self
+    }
+
+    /**
+    Maps the elements, drawing both them and their edges on the given layer.
+
+    Useful for markers that should stay on top of the network, or beneath it.
+
+
+    ## Examples
+
+    Highlights a Node on top of the network, including elements mapped afterwards.
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (20, 0), (40, 10)]));
+    let mut marker = Node::new("marker", Coordinate::new(20, 0));
+    marker.color = image::Rgba([230, 40, 40, 255]);
+    let image = Map::new()
+        .map(&nodes)
+        .map_layer(Layer::Overlay, &[marker])
+        .map(&nodes[1..])
+        .consume();
+    ```
+     */
+    pub fn map_layer<T: Draw + Location + Hash + MinMax>(
+        self,
+        layer: Layer,
+        element: &[T],
+    ) -> Self {
+        self.draw_on(element, &|_| true, &Shape::Square, Some(layer))
+    }
+
+    /**
+    Maps the elements on the given layer without adding their Hitboxes, for marks that are not elements of their own.
+     */
+    fn map_marks<T: Draw + Location + Hash + MinMax>(
+        mut self,
+        layer: Layer,
+        element: &[T],
+    ) -> Self {
+        let hitboxes = std::mem::take(&mut self.hitboxes);
+        let mut map = self.map_layer(layer, element);
+        map.hitboxes = hitboxes;
+        map
+    }
+
     /**
     Maps the elements but with all added parameters.
      */
     pub fn map_params<T: Draw + Location + Hash + MinMax>(
-        mut self,
+        self,
         element: &[T],
         filter: &Fn(&T) -> bool,
         shape: &Shape,
     ) -> Self {
-        if self.image.is_none() {
+        self.draw_on(element, filter, shape, None)
+    }
+
+    /**
+    Maps the elements like Map::map, but splits the image in to tiles drawn on separate threads.
+
+    The image is identical to the one drawn by Map::map.
+    Elements are only drawn on the tiles their bounds overlap, and on every tile if their bounds are not known.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut group = Group::new_simple(0, 0);
+    group.add(2000);
+    let parallel = Map::new().map_parallel(&[group.clone()]).consume();
+    let sequential = Map::new().map(&[group]).consume();
+    assert_eq!(parallel.image().as_ref() as &[u8], sequential.image().as_ref() as &[u8]);
+    ```
+     */
+    pub fn map_parallel<T: Draw + Location + Hash + MinMax + Sync>(
+        mut self,
+        element: &[T],
+    ) -> Self {
+        let (image, add) = match self.prepare(element) {
+            Some(prepared) => prepared,
+            None => return self,
+        };
+        let shape = Shape::Square;
+        let bounds = element
+            .iter()
+            .map(|x| x.bounds(&shape).map(|r| r.translate(add)))
+            .collect::<Vec<_>>();
+
+        let dim = image.dimensions();
+        let size = consts::TILE_SIZE as usize;
+        let tiles = (0..dim.y)
+            .step_by(size)
+            .flat_map(|y| {
+                (0..dim.x).step_by(size).map(move |x| {
+                    let max = coordinate!(x + size as i32, y + size as i32);
+                    let max = coordinate!(std::cmp::min(max.x, dim.x), std::cmp::min(max.y, dim.y));
+                    Rect::new(coordinate!(x, y), max - coordinate!(1, 1))
+                })
+            })
+            .collect::<Vec<_>>();
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk = std::cmp::max(tiles.len().div_ceil(threads), 1);
+
+        let drawn = std::thread::scope(|scope| {
+            let (image, bounds, shape) = (&*image, &bounds, &shape);
+            let handles = tiles
+                .chunks(chunk)
+                .map(|tiles| {
+                    scope.spawn(move || {
+                        tiles
+                            .iter()
+                            .map(|&tile| {
+                                let offset = add - tile.min;
+                                let mut drawn = image.crop(tile);
+                                element
+                                    .iter()
+                                    .zip(bounds.iter())
+                                    .enumerate()
+                                    .filter(|(_, (_, b))| b.is_none_or(|b| b.intersects(tile)))
+                                    .for_each(|(i, (x, _))| {
+                                        drawn.drawing(i);
+                                        x.draw(&mut drawn, offset, shape);
+                                    });
+                                (tile, drawn)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("a tile failed to be drawn"))
+                .collect::<Vec<_>>()
+        });
+
+        // Nodes drawn on several tiles are recorded on each, so their parts are joined.
+        let mut hits = std::collections::BTreeMap::new();
+        for (tile, mut drawn) in drawn {
+            for (key, hit) in drawn.hits.drain(..) {
+                let rect = hit.rect.translate(tile.min);
+                hits.entry(key)
+                    .and_modify(|h: &mut map::hitbox::Hitbox| h.rect = h.rect.union(rect))
+                    .or_insert(map::hitbox::Hitbox { rect, ..hit });
+            }
+            image.paste(tile, drawn);
+        }
+        self.hit(hits.into_values());
+        self
+    }
+
+    /**
+    Returns the image and the offset, creating the image if nothing has been mapped yet sized to fit the elements.
+
+    None if there is no image and no elements to size it by.
+     */
+    fn init<T: Draw + Location + Hash + MinMax>(
+        &mut self,
+        element: &[T],
+    ) -> Option<(&mut IW, Coordinate)> {
+        if self.image.is_none() && !element.is_empty() {
             let (image, add) = map::gen_map(&element);
-            self.image = Some(IW { img: image });
+            self.image = Some(IW::layered(image));
             self.add = add;
         }
-
         let add = self.add;
-        self.image = Some(
-            element
-                .iter()
-                .filter(|x| filter(x))
-                .fold(self.image.unwrap(), |img, x| x.draw(img, add, shape)),
-        );
+        self.image.as_mut().map(|image| (image, add))
+    }
+
+    /**
+    Returns the image and the offset, ready for the elements to be drawn on it.
+     */
+    fn prepare<T: Draw + Location + Hash + MinMax>(
+        &mut self,
+        element: &[T],
+    ) -> Option<(&mut IW, Coordinate)> {
+        let sprites = self.sprites.clone();
+        let (image, add) = self.init(element)?;
+        let obstacles = std::sync::Arc::make_mut(&mut image.obstacles);
+        for x in element {
+            obstacles.insert(x.min_max().translate(add));
+        }
+        image.sprites = sprites;
+        Some((image, add))
+    }
+
+    /**
+    Maps the elements, on the given layer if there is one, otherwise on the layers they usually are drawn on.
+     */
+    fn draw_on<T: Draw + Location + Hash + MinMax>(
+        mut self,
+        element: &[T],
+        filter: &Fn(&T) -> bool,
+        shape: &Shape,
+        layer: Option<Layer>,
+    ) -> Self {
+        let (image, add) = match self.prepare(element) {
+            Some(prepared) => prepared,
+            None => return self,
+        };
+        image.forced = layer;
+        for (i, x) in element.iter().enumerate().filter(|(_, x)| filter(x)) {
+            image.drawing(i);
+            x.draw(image, add, shape);
+        }
+        image.forced = None;
+        let hits = std::mem::take(&mut image.hits);
+        self.hit(hits.into_iter().map(|(_, hit)| hit));
         self
     }
+
+    /**
+    Adds the Hitboxes of the elements just mapped, keeping them ordered by layer and z-index.
+
+    The sort is stable, so Hitboxes on the same layer and z-index stay in the order they were drawn.
+     */
+    fn hit<I: IntoIterator<Item = map::hitbox::Hitbox>>(&mut self, hits: I) {
+        self.hitboxes.extend(hits);
+        self.hitboxes.sort_by_key(|hitbox| (hitbox.layer, hitbox.z));
+    }
 }
 
 impl Network<Node> {
@@ -1607,6 +3867,35 @@ impl Network<Node> {
         Ok(path)
     }
 
+    /**
+    Calculates the path from node A to node B, weighting each leg using the given function.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::from_list(&[(0, 0), (10, 10), (20, 20), (30, 30)]);
+    let nodes = Node::linked_list(nodes);
+    let path = Network::new(nodes)
+        .path_weighted("A", "D", &|a, b| coordinate::distance(a, b) * 2)
+        .unwrap();
+    assert_eq!(path.len(), 4);
+    ```
+     */
+    pub fn path_weighted(
+        &self,
+        a: &str,
+        b: &str,
+        weight: &Fn(Coordinate, Coordinate) -> u32,
+    ) -> std::io::Result<Vec<Node>> {
+        let mut path = map::network::path(self, b, a, &|net, start, goal| {
+            map::network::path_shortest_leg_weighted(net, start, goal, weight)
+        })?;
+        path.reverse();
+        Ok(path)
+    }
+
     /**
     Mimics path behaviour but works in reverse, Meaning stepping back in the links.
      */
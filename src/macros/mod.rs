@@ -21,9 +21,10 @@ These provide a less-strict typed struct versions which lightens the syntax.
   # }
   ```
 
-  Type conversion is performed to make invocation easier.
+  Type conversion is performed to make invocation easier, floats are truncated towards zero.
 
-  If you are not sure if the value you pass can be converted to a i16, do not use this macro as values could overflow.
+  The values are converted with coordinate::Component, which panics if they do not fit in an i32,
+  rather than letting them wrap around. Use Coordinate::new with values you have converted yourself to handle these cases.
 
   ```
   # #![macro_use] use pathtracer::*;
@@ -33,26 +34,36 @@ These provide a less-strict typed struct versions which lightens the syntax.
   # }
   ```
 
+  ```should_panic
+  # #![macro_use] use pathtracer::*;
+  # fn main() {
+  coordinate!(u32::max_value(), 0);
+  # }
+  ```
+
 */
 #[macro_export]
 macro_rules! coordinate {
     () => {
-        coordinate!(0, 0);
+        coordinate!(0, 0)
     };
 
     ($c:expr) => {
-        coordinate!($c, $c);
+        coordinate!($c, $c)
     };
 
     ($x:expr, $y:expr) => {
-        Coordinate::new($x as i16, $y as i16);
+        Coordinate::new(
+            $crate::coordinate::Component::to_i32($x),
+            $crate::coordinate::Component::to_i32($y),
+        )
     };
 }
 
 /**
   initalize Nodes using a range of parameters.
 
-  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i16. it is better to avoid these macro invocations.
+  Since the macro calls coordinate! it allows for type conversion, and panics if the values do not fit in an i32.
 
   ## Examples
 
@@ -73,26 +84,26 @@ macro_rules! coordinate {
 #[macro_export]
 macro_rules! node {
     () => {
-        node!(0, 0);
+        node!(0, 0)
     };
 
     ($c:expr) => {
-        node!($c.x, $c.y);
+        node!($c.x, $c.y)
     };
 
     ($x:expr, $y:expr) => {
-        node!(&format!("{},{}", $x, $y), $x, $y);
+        node!(&format!("{},{}", $x, $y), $x, $y)
     };
 
     ($name:expr, $x:expr, $y:expr) => {
-        Node::new($name, coordinate!($x, $y));
+        Node::new($name, coordinate!($x, $y))
     };
 }
 
 /**
   initalize Groups using a range of parameters.
 
-  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i16. it is better to avoid these macro invocations.
+  Since the macro calls coordinate! it allows for type conversion, and panics if the values do not fit in an i32.
 
 
   ## Examples
@@ -114,19 +125,19 @@ macro_rules! node {
 #[macro_export]
 macro_rules! cluster {
     () => {
-        cluster!(0, 0);
+        cluster!(0, 0)
     };
 
     ($c:expr) => {
-        cluster!($c.x, $c.y);
+        cluster!($c.x, $c.y)
     };
 
     ($x:expr, $y:expr) => {
-        cluster!(&format!("{},{}", $x, $y), $x, $y);
+        cluster!(&format!("{},{}", $x, $y), $x, $y)
     };
 
     ($name:expr, $x:expr, $y:expr) => {
-        Group::new($name, coordinate!($x, $y));
+        Group::new($name, coordinate!($x, $y))
     };
 }
 
@@ -1,38 +1,210 @@
 /*!
   Gif wrapper for managing moving images.
 
-  Applied logic for cycles of Nodes.
+  Frames are written as they are pushed.
 */
 
 use super::super::*;
 use gif::{self, *};
-use std::{fs::File, io};
+use image::Rgba;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
 
-struct Cycle<'a, T: Draw + Location + Hash + MinMax + Copy> {
-    interval: u8,
-    count: u8,
-    map: Vec<T>,
-    predicate: &'a Fn(&T) -> T,
+/**
+Palette index used for pixels that did not change since the previous frame.
+*/
+const TRANSPARENT: u8 = 0;
+
+/**
+Palette index used for fully transparent pixels on the Map.
+*/
+const BACKGROUND: u8 = 1;
+
+/**
+A global color palette shared by every frame in a Gif.
+
+Holds at most 254 colors, as two entries are reserved for unchanged pixels and the background.
+Colors not in the palette are drawn using the closest color that is.
+*/
+#[derive(Clone, Debug)]
+pub struct Palette {
+    colors: Vec<Rgba<u8>>,
+    background: Rgba<u8>,
+    lookup: HashMap<[u8; 3], u8>,
+}
+
+impl Palette {
+    /**
+    Constructs a Palette from a list of colors, duplicates are removed.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::gif::Palette;
+    let red = image::Rgba([255, 0, 0, 255]);
+    let mut palette = Palette::new(&[red, red]);
+    assert_eq!(palette.len(), 1);
+    assert_eq!(palette.index(image::Rgba([250, 10, 0, 255])), palette.index(red));
+    ```
+    */
+    pub fn new(colors: &[Rgba<u8>]) -> Self {
+        let mut palette = Palette {
+            colors: Vec::new(),
+            background: consts::DEFAULT_RGBA,
+            lookup: HashMap::new(),
+        };
+        for color in colors {
+            let key = [color.data[0], color.data[1], color.data[2]];
+            if palette.colors.len() < 254 && !palette.lookup.contains_key(&key) {
+                palette.colors.push(*color);
+                palette.lookup.insert(key, palette.colors.len() as u8 + 1);
+            }
+        }
+        palette
+    }
+
+    /**
+    Constructs a Palette from the colors used in an image.
+
+    If more than 254 colors are used, the most common ones are kept.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::Palette, *};
+    let mut node = Node::new("A", Coordinate::new(10, 10));
+    node.color = image::Rgba([200, 30, 30, 255]);
+    let image = Map::new().map(&[node]).consume();
+    let palette = Palette::from_image(&image);
+    assert!(palette.colors().contains(&node.color));
+    ```
+    */
+    pub fn from_image(image: &IW) -> Self {
+        let mut count: HashMap<[u8; 3], (usize, Rgba<u8>)> = HashMap::new();
+        for pix in image.image().pixels().filter(|p| p.data[3] != 0) {
+            let entry = count
+                .entry([pix.data[0], pix.data[1], pix.data[2]])
+                .or_insert((0, *pix));
+            entry.0 += 1;
+        }
+
+        let mut colors = count.values().cloned().collect::<Vec<_>>();
+        colors.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.data.cmp(&b.1.data)));
+        Palette::new(&colors.iter().map(|x| x.1).collect::<Vec<_>>())
+    }
+
+    /**
+    Sets the color drawn for fully transparent pixels on the Map, which semi-transparent pixels are blended with.
+    */
+    pub fn background(&mut self, color: Rgba<u8>) {
+        self.background = color;
+    }
+
+    /**
+    Returns the colors in the palette, excluding the reserved entries.
+    */
+    pub fn colors(&self) -> &[Rgba<u8>] {
+        &self.colors
+    }
+
+    /**
+    Returns the number of colors in the palette, excluding the reserved entries.
+    */
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /**
+    Returns true if the palette has no colors, excluding the reserved entries.
+    */
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /**
+    Returns the palette index to draw the color with.
+
+    Semi-transparent colors are first composited over the background, as the gif has no partial transparency.
+    Unknown colors are matched to the closest color and remembered for the next lookup.
+    */
+    pub fn index(&mut self, color: Rgba<u8>) -> u8 {
+        let alpha = u32::from(color.data[3]);
+        if alpha == 0 {
+            return BACKGROUND;
+        }
+
+        let mut key = [0; 3];
+        for (i, channel) in key.iter_mut().enumerate() {
+            let (fg, bg) = (u32::from(color.data[i]), u32::from(self.background.data[i]));
+            *channel = ((fg * alpha + bg * (255 - alpha) + 127) / 255) as u8;
+        }
+        if let Some(&i) = self.lookup.get(&key) {
+            return i;
+        }
+
+        let distance = |c: &Rgba<u8>| {
+            (0..3)
+                .map(|i| (i32::from(c.data[i]) - i32::from(key[i])).pow(2))
+                .sum::<i32>()
+        };
+        let i = std::iter::once(&self.background)
+            .chain(self.colors.iter())
+            .enumerate()
+            .min_by_key(|&(_, c)| distance(c))
+            .map(|(i, _)| i as u8 + BACKGROUND)
+            .unwrap_or(BACKGROUND);
+        self.lookup.insert(key, i);
+        i
+    }
+
+    /**
+    Returns the color table in the format expected by the gif encoder.
+    */
+    fn table(&self) -> Vec<u8> {
+        let mut table = vec![0, 0, 0];
+        for color in std::iter::once(&self.background).chain(self.colors.iter()) {
+            table.extend_from_slice(&color.data[0..3]);
+        }
+        table
+    }
+}
+
+/**
+Counts the bytes written to the underlying writer.
+
+The writer is shared with the Gif, so it can be taken back once the Gif is finished.
+Anything written after that, such as the trailer the encoder writes when it is dropped, is discarded.
+*/
+struct Counter<W: Write> {
+    inner: Arc<Mutex<Option<W>>>,
+    size: Arc<AtomicU64>,
 }
 
-impl<'a, T: Draw + Location + Hash + MinMax + Copy> Cycle<'a, T> {
-    pub fn new(interval: u8, map: Vec<T>, predicate: &'a Fn(&T) -> T) -> Self {
-        Cycle {
-            interval,
-            map,
-            predicate,
-            count: interval,
+impl<W: Write> Write for Counter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.inner.lock().unwrap().as_mut() {
+            Some(inner) => {
+                let n = inner.write(buf)?;
+                self.size.fetch_add(n as u64, Ordering::Relaxed);
+                Ok(n)
+            }
+            None => Ok(buf.len()),
         }
     }
 
-    pub fn then(&mut self) -> Option<Vec<T>> {
-        self.count -= 1;
-        if self.count == 0 {
-            self.count = self.interval;
-            self.map = self.map.iter().map(self.predicate).collect::<_>();
-            Some(self.map.clone())
-        } else {
-            None
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner.lock().unwrap().as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
         }
     }
 }
@@ -40,112 +212,313 @@ impl<'a, T: Draw + Location + Hash + MinMax + Copy> Cycle<'a, T> {
 /**
 Wrapper around the Gif struct found in the gif crate.
 
-With additions for pathtracer support, and cycling features.
+With additions for pathtracer support.
+
+Use map::timeline to animate Nodes between frames.
+
+The Gif can be written to any std::io::Write, it defaults to a File.
+The trailer of the Gif is written by Gif::finish, or once it is dropped.
 */
-pub struct Gif<'a> {
-    encoder: gif::Encoder<File>,
-    cycles: Vec<Cycle<'a, Node>>,
-    frames: u16,
+pub struct Gif<W: Write = File> {
+    encoder: gif::Encoder<Counter<W>>,
+    frames: usize,
     width: u16,
     height: u16,
+    delay: u16,
+    dispose: DisposalMethod,
+    repeat: Option<Repeat>,
+    last: Option<Frame<'static>>,
+    palette: Option<Palette>,
+    canvas: Option<Vec<u8>>,
+    writer: Arc<Mutex<Option<W>>>,
+    size: Arc<AtomicU64>,
 }
 
-impl<'a> Gif<'a> {
+impl Gif<File> {
     /**
     Constructs a Gif struct and initializes a file on the system for the Gif to be stored.
 
 
-    ## Errors
+    ## Panics
 
     If it is unable to create the Encoder, or if the file can not be created.
+    Use Gif::from_writer to handle these errors.
 
 
     ## See also
 
     examples/hello_world_gif.rs
-    example/cycles.rs
+    examples/timeline.rs
 
     */
     pub fn new(filename: &str, width: u16, height: u16) -> Self {
+        Gif::from_writer(File::create(filename).unwrap(), width, height).unwrap()
+    }
+
+    /**
+    Constructs a Gif that draws every frame using a shared global palette.
+
+    Only the area that changed since the previous frame is written, which keeps long animations small.
+    Since frames build on each other, they are never disposed.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::*, *};
+    # fn main() -> std::io::Result<()> {
+    let nodes = Node::from_list(&[(10, 10), (30, 30)]);
+    let palette = Palette::from_image(&Map::new().map(&nodes).consume());
+    let mut gif = Gif::new_palette("/tmp/palette.gif", 50, 50, palette);
+    for _ in 0..5 {
+        gif.push(Map::new().map(&nodes))?;
+    }
+    assert!(gif.size() > 0);
+    # Ok(())
+    # }
+    ```
+
+
+    ## Panics
+
+    If it is unable to create the Encoder, or if the file can not be created.
+    Use Gif::from_writer_palette to handle these errors.
+    */
+    pub fn new_palette(filename: &str, width: u16, height: u16, palette: Palette) -> Self {
         let file = File::create(filename).unwrap();
-        let mut encoder = Encoder::new(file, width, height, &[]).unwrap();
-        encoder.set(Repeat::Infinite).unwrap();
-        Gif {
+        Gif::from_writer_palette(file, width, height, palette).unwrap()
+    }
+}
+
+impl<W: Write> Gif<W> {
+    /**
+    Constructs a Gif that is encoded into the writer.
+
+
+    ## Examples
+
+    Encodes the Gif in memory.
+
+    ```
+    # use pathtracer::{map::gif::Gif, *};
+    # fn main() -> std::io::Result<()> {
+    let mut bytes = Vec::new();
+    {
+        let mut gif = Gif::from_writer(&mut bytes, 50, 50)?;
+        gif.blank()?;
+    }
+    assert_eq!(&bytes[..6], b"GIF89a");
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If the header can not be written.
+    */
+    pub fn from_writer(writer: W, width: u16, height: u16) -> Result<Self, io::Error> {
+        Gif::create(writer, width, height, None)
+    }
+
+    /**
+    Constructs a Gif that is encoded into the writer using a shared global palette.
+
+    See Gif::new_palette.
+
+
+    ## Errors
+
+    If the header can not be written.
+    */
+    pub fn from_writer_palette(
+        writer: W,
+        width: u16,
+        height: u16,
+        palette: Palette,
+    ) -> Result<Self, io::Error> {
+        Gif::create(writer, width, height, Some(palette))
+    }
+
+    fn create(
+        writer: W,
+        width: u16,
+        height: u16,
+        palette: Option<Palette>,
+    ) -> Result<Self, io::Error> {
+        let writer = Arc::new(Mutex::new(Some(writer)));
+        let size = Arc::new(AtomicU64::new(0));
+        let counter = Counter {
+            inner: writer.clone(),
+            size: size.clone(),
+        };
+        let table = palette.as_ref().map(|p| p.table()).unwrap_or_default();
+        let encoder = Encoder::new(counter, width, height, &table)?;
+        Ok(Gif {
             encoder,
-            cycles: Vec::new(),
             frames: 0,
             width,
             height,
-        }
+            delay: consts::DEFAULT_GIF_DELAY,
+            dispose: DisposalMethod::Background,
+            repeat: Some(Repeat::Infinite),
+            last: None,
+            palette,
+            canvas: None,
+            writer,
+            size,
+        })
     }
 
     /**
-    Adds in a repeating patttern every interval frame on to the gif image.
+    Returns the number of bytes written so far.
+    */
+    pub fn size(&self) -> u64 {
+        self.size.load(Ordering::Relaxed)
+    }
 
+    /**
+    Writes the trailer of the Gif and returns the writer it was encoded into.
 
-    ## See also
 
-    examples/cycles.rs
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::Gif, *};
+    # fn main() -> std::io::Result<()> {
+    let mut gif = Gif::from_writer(Vec::new(), 50, 50)?;
+    gif.blank()?;
+    let bytes = gif.finish()?;
+    assert_eq!(bytes.last(), Some(&0x3B));
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If the trailer can not be written, or the writer fails to flush.
     */
-    pub fn cycle(&mut self, interval: u8, map: Vec<Node>) {
-        self.cycles.push(Cycle::new(interval, map, &|x| *x));
+    pub fn finish(self) -> Result<W, io::Error> {
+        let Gif {
+            encoder,
+            writer,
+            size,
+            ..
+        } = self;
+        let mut writer = writer.lock().unwrap().take().unwrap();
+        drop(encoder);
+        writer.write_all(&[Block::Trailer as u8])?;
+        size.fetch_add(1, Ordering::Relaxed);
+        writer.flush()?;
+        Ok(writer)
     }
 
     /**
-    Adds in a repeating patttern every interval frame on to the gif image.
-    */
-    pub fn cycle_predicate(
-        &mut self,
-        interval: u8,
-        map: Vec<Node>,
-        predicate: &'a Fn(&Node) -> Node,
-    ) {
-        self.cycles.push(Cycle::new(interval, map, predicate));
+    Sets the default delay between frames, in units of 10 ms.
+
+    Applies to all frames pushed after it is set, unless given a delay of their own.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::gif::Gif;
+    let mut gif = Gif::new("/tmp/delay.gif", 50, 50);
+    gif.delay(5);
+    ```
+     */
+    pub fn delay(&mut self, delay: u16) {
+        self.delay = delay;
     }
 
     /**
-    Removes all cycles from the gif.
-    */
-    pub fn remove_cycles(&mut self) {
-        self.cycles = Vec::new();
+    Sets how each frame is disposed before the next frame is drawn.
+
+    Applies to all frames pushed after it is set.
+     */
+    pub fn dispose(&mut self, dispose: DisposalMethod) {
+        self.dispose = dispose;
     }
 
     /**
-    Advances the cycles and returns the patterns it matched.
-    */
-    pub fn advance_cycle(&mut self) -> Vec<Node> {
-        let mut result = Vec::new();
-        for cycle in self.cycles.iter_mut() {
-            if let Some(mut e) = cycle.then() {
-                result.append(&mut e)
-            }
+    Sets the number of times the gif is played, it defaults to looping infinitely.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::gif::Gif;
+    # fn main() -> std::io::Result<()> {
+    let mut gif = Gif::new("/tmp/repeat.gif", 50, 50);
+    gif.repeat(gif::Repeat::Finite(3))?;
+    gif.blank()?;
+    assert!(gif.repeat(gif::Repeat::Infinite).is_err());
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If a frame has already been written, since the repeat count is stored before the first frame.
+     */
+    pub fn repeat(&mut self, repeat: Repeat) -> Result<(), io::Error> {
+        if self.frames > 0 {
+            return Err(io::Error::other(
+                "repeat must be set before the first frame is written",
+            ));
         }
-        result
+        self.repeat = Some(repeat);
+        Ok(())
     }
 
     /**
     Returns the number of frames that has been written.
     */
-    pub fn frames(&self) -> u16 {
+    pub fn frames(&self) -> usize {
         self.frames
     }
 
     /**
     Pushes a frame using a map struct.
     */
-    pub fn push(&mut self, mut map: Map) -> Result<(), io::Error> {
-        map = map.map(&self.advance_cycle());
-        self.push_frame(&map.consume())
+    pub fn push(&mut self, map: Map) -> Result<(), io::Error> {
+        let delay = self.delay;
+        self.push_delay(map, delay)
+    }
+
+    /**
+    Pushes a frame using a map struct, shown for the given delay in units of 10 ms.
+    */
+    pub fn push_delay(&mut self, map: Map, delay: u16) -> Result<(), io::Error> {
+        self.push_frame_delay(&map.consume(), delay)
     }
 
     /**
-    Pushes a frame to the structure, This also immediately saves it to disk.
+    Pushes a frame to the structure, This also immediately writes it.
 
     ## Errors
 
-    If the encoder fails to write the frame to disk.
+    If the encoder fails to write the frame.
     */
     pub fn push_frame(&mut self, image: &IW) -> Result<(), io::Error> {
+        let delay = self.delay;
+        self.push_frame_delay(image, delay)
+    }
+
+    /**
+    Pushes a frame to the structure, shown for the given delay in units of 10 ms.
+
+    ## Errors
+
+    If the encoder fails to write the frame.
+    */
+    pub fn push_frame_delay(&mut self, image: &IW, delay: u16) -> Result<(), io::Error> {
+        if self.palette.is_some() {
+            return self.push_frame_diff(image, delay);
+        }
+
         let mut pixels: Vec<u8> = Vec::new();
         for pix in image.image().pixels() {
             for i in 0..4 {
@@ -155,25 +528,134 @@ impl<'a> Gif<'a> {
 
         let dim = image.dimensions();
         let mut frame = Frame::from_rgba(dim.x as u16, dim.y as u16, &mut pixels);
-        frame.dispose = DisposalMethod::Background;
-        frame.delay = 20;
+        frame.dispose = self.dispose;
+        frame.delay = delay;
+        self.write(frame)
+    }
+
+    /**
+    Writes the area of the image that changed since the previous frame using the palette.
+    */
+    fn push_frame_diff(&mut self, image: &IW, delay: u16) -> Result<(), io::Error> {
+        let (w, h) = (usize::from(self.width), usize::from(self.height));
+        let img = image.image();
+        let palette = self.palette.as_mut().unwrap();
+        let mut target = vec![BACKGROUND; w * h];
+        for (x, y, pix) in img.enumerate_pixels() {
+            if (x as usize) < w && (y as usize) < h {
+                target[y as usize * w + x as usize] = palette.index(*pix);
+            }
+        }
+
+        // Find the area that differs from what is currently shown.
+        let mut changed: Option<Rect> = None;
+        if let Some(canvas) = &self.canvas {
+            for (i, (a, b)) in canvas.iter().zip(target.iter()).enumerate() {
+                if a != b {
+                    let c = coordinate!(i % w, i / w);
+                    changed = Some(changed.map_or(Rect::new(c, c), |r| r.union(Rect::new(c, c))));
+                }
+            }
+        } else if w > 0 && h > 0 {
+            changed = Some(Rect::new(coordinate!(), coordinate!(w - 1, h - 1)));
+        }
+
+        let mut frame = match changed {
+            Some(rect) => {
+                let mut pixels = Vec::new();
+                for y in rect.min.y..=rect.max.y {
+                    for x in rect.min.x..=rect.max.x {
+                        let i = y as usize * w + x as usize;
+                        let same = self.canvas.as_ref().is_some_and(|c| c[i] == target[i]);
+                        pixels.push(if same { TRANSPARENT } else { target[i] });
+                    }
+                }
+                let size = rect.size() + coordinate!(1);
+                let mut frame = Frame::from_indexed_pixels(
+                    size.x as u16,
+                    size.y as u16,
+                    &pixels,
+                    Some(TRANSPARENT),
+                );
+                frame.left = rect.min.x as u16;
+                frame.top = rect.min.y as u16;
+                frame
+            }
+            None => Frame::from_indexed_pixels(1, 1, &[TRANSPARENT], Some(TRANSPARENT)),
+        };
+
+        frame.dispose = DisposalMethod::Keep;
+        frame.delay = delay;
+        self.canvas = Some(target);
+        self.write(frame)
+    }
+
+    /**
+    Shows the last written frame again for the given delay in units of 10 ms.
+
+    Useful to pause on the final frame before the gif loops.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::gif::Gif;
+    # fn main() -> std::io::Result<()> {
+    let mut gif = Gif::new("/tmp/hold.gif", 50, 50);
+    gif.blank()?;
+    gif.hold(200)?;
+    assert_eq!(gif.frames(), 2);
+    # Ok(())
+    # }
+    ```
+
+
+    ## Errors
+
+    If no frame has been written yet, or if the encoder fails to write the frame.
+    */
+    pub fn hold(&mut self, delay: u16) -> Result<(), io::Error> {
+        let mut frame = self
+            .last
+            .clone()
+            .ok_or_else(|| io::Error::other("no frame has been written to hold"))?;
+        frame.delay = delay;
+        self.write(frame)
+    }
+
+    /**
+    Writes the frame with the encoder, writing the repeat count first if it is the first frame.
+    */
+    fn write(&mut self, frame: Frame<'static>) -> Result<(), io::Error> {
+        if let Some(repeat) = self.repeat.take() {
+            self.encoder.set(repeat)?;
+        }
         self.encoder.write_frame(&frame)?;
+        self.last = Some(frame);
         self.frames += 1;
         Ok(())
     }
 
     /**
     Appends a blank frame to the gif.
-
-    This will also advance Gif cycles.
     */
     pub fn blank(&mut self) -> Result<(), io::Error> {
-        let mut node = node!(self.width as i16 - 1, self.height as i16 - 1);
+        let mut node = node!(i32::from(self.width) - 1, i32::from(self.height) - 1);
         node.radius = Some(0);
         self.push(Map::new().map(&[node]))
     }
 }
 
+impl<W: Write> Sink for Gif<W> {
+    fn push(&mut self, map: Map) -> Result<(), io::Error> {
+        Gif::push(self, map)
+    }
+
+    fn push_frame_delay(&mut self, image: &IW, delay: u16) -> Result<(), io::Error> {
+        Gif::push_frame_delay(self, image, delay)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,40 +683,147 @@ mod tests {
     }
 
     #[test]
-    fn cycles_predicate() {
+    fn frame_timing() {
         define(&|mut gif| {
-            gif.cycle_predicate(1, vec![node!(25, 25)], &|x| {
-                let mut x = x.clone();
-                x.geo.x += 5;
-                x
-            });
-
-            for i in 0..3 {
-                assert_eq!(gif.advance_cycle()[0].x(), 30 + i * 5);
-            }
+            gif.delay(7);
+            gif.dispose(DisposalMethod::Keep);
+            gif.blank()?;
+            let last = gif.last.clone().unwrap();
+            assert_eq!(last.delay, 7);
+            assert_eq!(last.dispose, DisposalMethod::Keep);
+            Ok(())
+        });
+    }
 
+    #[test]
+    fn hold_without_frames() {
+        define(&|mut gif| {
+            assert!(gif.hold(100).is_err());
+            assert_eq!(gif.frames(), 0);
             Ok(())
         });
     }
 
     #[test]
-    fn cycles_every_frame() {
+    fn hold_last_frame() {
         define(&|mut gif| {
-            gif.cycle(1, vec![node!(25, 25)]);
-            for _ in 0..3 {
-                assert_eq!(gif.advance_cycle().len(), 1);
-            }
+            gif.blank()?;
+            gif.hold(300)?;
+            assert_eq!(gif.last.clone().unwrap().delay, 300);
+            assert_eq!(gif.frames(), 2);
             Ok(())
         });
     }
 
     #[test]
-    fn cycles_every_other() {
+    fn repeat_before_frames() {
         define(&|mut gif| {
-            gif.cycle(2, vec![node!(25, 25)]);
-            assert!(gif.advance_cycle().is_empty());
-            assert_eq!(gif.advance_cycle().len(), 1);
+            gif.repeat(Repeat::Finite(2))?;
+            gif.blank()?;
+            assert!(gif.repeat(Repeat::Finite(1)).is_err());
             Ok(())
         });
     }
+
+    #[test]
+    fn write_to_memory() -> std::io::Result<()> {
+        let mut bytes = Vec::new();
+        let size = {
+            let mut gif = Gif::from_writer(&mut bytes, 50, 50)?;
+            gif.blank()?;
+            gif.size()
+        };
+        assert_eq!(bytes.len() as u64, size + 1);
+        assert_eq!(bytes.last(), Some(&0x3B));
+        Ok(())
+    }
+
+    #[test]
+    fn finish_returns_writer() -> std::io::Result<()> {
+        let mut gif = Gif::from_writer(Vec::new(), 50, 50)?;
+        gif.blank()?;
+        let size = gif.size();
+        let bytes = gif.finish()?;
+        assert_eq!(bytes.len() as u64, size + 1);
+        assert_eq!(bytes.last(), Some(&0x3B));
+        Ok(())
+    }
+
+    #[test]
+    fn finish_reports_errors() {
+        struct Full;
+        impl Write for Full {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::Error::other("full"))
+            }
+        }
+        let gif = Gif::from_writer(Full, 50, 50).unwrap();
+        assert!(gif.finish().is_err());
+    }
+
+    #[test]
+    fn gif_is_send() {
+        fn send<T: Send>(_: T) {}
+        send(Gif::from_writer(Vec::new(), 50, 50).unwrap());
+    }
+
+    #[test]
+    fn palette_reserved_entries() {
+        let red = Rgba([255, 0, 0, 255]);
+        let mut palette = Palette::new(&[red]);
+        assert_eq!(palette.index(Rgba([0, 0, 0, 0])), BACKGROUND);
+        assert_eq!(palette.index(red), 2);
+        assert_eq!(palette.table().len(), 9);
+    }
+
+    #[test]
+    fn palette_max_colors() {
+        let colors = (0..300)
+            .map(|i| Rgba([(i % 256) as u8, (i / 256) as u8, 0, 255]))
+            .collect::<Vec<_>>();
+        let palette = Palette::new(&colors);
+        assert_eq!(palette.len(), 254);
+    }
+
+    #[test]
+    fn palette_nearest() {
+        let mut palette = Palette::new(&[Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255])]);
+        assert_eq!(palette.index(Rgba([0, 10, 200, 255])), 3);
+        assert_eq!(palette.index(Rgba([10, 10, 10, 255])), BACKGROUND);
+    }
+
+    #[test]
+    fn palette_composites_over_background() {
+        let (red, white) = (Rgba([255, 0, 0, 255]), Rgba([255, 255, 255, 255]));
+        let mut palette = Palette::new(&[red, Rgba([255, 128, 128, 255])]);
+        palette.background(white);
+        assert_eq!(palette.index(Rgba([255, 0, 0, 128])), 3);
+        assert_eq!(palette.index(Rgba([255, 0, 0, 10])), BACKGROUND);
+        assert_eq!(palette.index(Rgba([255, 0, 0, 250])), 2);
+    }
+
+    #[test]
+    fn palette_diff_frames() -> std::io::Result<()> {
+        let nodes = Node::from_list(&[(10, 10), (30, 30)]);
+        let palette = Palette::from_image(&Map::new().map(&nodes).consume());
+        let mut gif = Gif::new_palette("/tmp/test_gif_palette.gif", 50, 50, palette);
+
+        gif.push(Map::new().map(&nodes))?;
+        let first = gif.last.clone().unwrap();
+        assert_eq!((first.width, first.height), (50, 50));
+
+        gif.push(Map::new().map(&nodes))?;
+        let unchanged = gif.last.clone().unwrap();
+        assert_eq!((unchanged.width, unchanged.height), (1, 1));
+
+        let moved = Node::from_list(&[(10, 10), (32, 30)]);
+        gif.push(Map::new().map(&moved))?;
+        let changed = gif.last.clone().unwrap();
+        assert!(changed.width < 50 && changed.height < 50);
+        assert!(gif.size() > 0);
+        Ok(())
+    }
 }
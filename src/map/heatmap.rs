@@ -0,0 +1,247 @@
+/*!
+Renders the density of elements instead of the elements themselves.
+
+Useful for Maps with so many Nodes that they overlap and become noise.
+ */
+
+use super::*;
+
+/**
+Accumulates positions into a grid of cells and paints how dense each cell is.
+
+Every position is spread over the cells within the radius, weighted by the distance to them.
+The densest cell is painted with the color, and less dense cells are darker and more transparent.
+
+
+## Examples
+
+Draws the density underneath the Nodes.
+
+```
+# use pathtracer::{map::heatmap::Heatmap, *};
+let nodes = Node::from_list(&[(0, 0), (4, 4), (8, 2), (60, 60)]);
+let heatmap = Heatmap::new().cell(4).radius(3);
+let image = Map::new()
+    .map_density(&nodes, &heatmap)
+    .map(&nodes)
+    .consume();
+```
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Heatmap {
+    pub cell: u32,
+    pub radius: u32,
+    pub color: Rgba<u8>,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Heatmap {
+            cell: 4,
+            radius: 2,
+            color: Rgba([255, 90, 30, 255]),
+        }
+    }
+}
+
+impl Heatmap {
+    /**
+    Constructs a Heatmap with 4 pixel cells and a radius of 2 cells.
+     */
+    pub fn new() -> Self {
+        Heatmap::default()
+    }
+
+    /**
+    Sets the width and height of each cell in pixels.
+     */
+    pub fn cell(mut self, cell: u32) -> Self {
+        self.cell = cmp::max(cell, 1);
+        self
+    }
+
+    /**
+    Sets how many cells each position is spread across, 0 only counts the cell it is in.
+     */
+    pub fn radius(mut self, radius: u32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /**
+    Sets the color of the densest cell.
+     */
+    pub fn color(mut self, color: Rgba<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /**
+    Returns the density of every cell covering an image of the given dimensions, row by row.
+
+    Positions are moved by the offset before they are placed in a cell, and positions outside of the image are ignored.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::heatmap::Heatmap, *};
+    let positions = Coordinate::from_list(&[(1, 1), (2, 2), (9, 9)]);
+    let grid = Heatmap::new().cell(5).radius(0).grid(&positions, coordinate!(), coordinate!(10, 10));
+    assert_eq!(grid, vec![2.0, 0.0, 0.0, 1.0]);
+    ```
+     */
+    pub fn grid(&self, positions: &[Coordinate], offset: Coordinate, dim: Coordinate) -> Vec<f64> {
+        let (cols, rows) = self.cells(dim);
+        let mut grid = vec![0.0; (cols * rows) as usize];
+        let r = self.radius as i32;
+        let cell = self.cell as i32;
+
+        for p in positions.iter().map(|&p| p + offset) {
+            if p.x < 0 || p.y < 0 || p.x >= dim.x || p.y >= dim.y {
+                continue;
+            }
+            let (cx, cy) = (p.x / cell, p.y / cell);
+            for y in cmp::max(cy - r, 0)..cmp::min(cy + r + 1, rows) {
+                for x in cmp::max(cx - r, 0)..cmp::min(cx + r + 1, cols) {
+                    let distance = Coordinate::new(x - cx, y - cy).length();
+                    let weight = 1.0 - distance / f64::from(r + 1);
+                    if weight > 0.0 {
+                        grid[(y * cols + x) as usize] += weight;
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    /**
+    Returns the color of a cell, given its density relative to the densest cell.
+
+    The colors are calculated using tools::range_color, darkening the color as the density decreases.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::heatmap::Heatmap;
+    let heatmap = Heatmap::new();
+    assert_eq!(heatmap.ramp(1.0), heatmap.color);
+    assert_eq!(heatmap.ramp(0.0)[3], 0);
+    ```
+     */
+    pub fn ramp(&self, density: f64) -> Rgba<u8> {
+        let density = density.clamp(0.0, 1.0);
+        let distance = ((1.0 - density) * f64::from(RAMP_FALLOFF)) as i32;
+        let mut color = tools::range_color(
+            RAMP_FALLOFF,
+            self.color,
+            coordinate!(),
+            coordinate!(distance, distance),
+        );
+        color[3] = (f64::from(self.color[3]) * density).round() as u8;
+        color
+    }
+
+    /**
+    Paints the density of the positions on to the image, leaving cells without any density untouched.
+
+    The cells are blended with what is drawn beneath them.
+     */
+    pub fn draw(&self, image: &mut IW, positions: &[Coordinate], offset: Coordinate) {
+        let dim = image.dimensions();
+        let grid = self.grid(positions, offset, dim);
+        let max = grid.iter().cloned().fold(0.0, f64::max);
+        if max <= 0.0 {
+            return;
+        }
+
+        let (cols, _) = self.cells(dim);
+        let cell = self.cell as i32;
+        for (i, density) in grid.iter().enumerate().filter(|(_, &d)| d > 0.0) {
+            let color = self.ramp(density / max);
+            let min = coordinate!(i as i32 % cols * cell, i as i32 / cols * cell);
+            for y in min.y..cmp::min(min.y + cell, dim.y) {
+                for x in min.x..cmp::min(min.x + cell, dim.x) {
+                    image.blend(x as u32, y as u32, color);
+                }
+            }
+        }
+    }
+
+    /**
+    Returns the number of columns and rows of cells needed to cover the dimensions.
+     */
+    fn cells(&self, dim: Coordinate) -> (i32, i32) {
+        let cell = self.cell as i32;
+        (
+            (cmp::max(dim.x, 0) + cell - 1) / cell,
+            (cmp::max(dim.y, 0) + cell - 1) / cell,
+        )
+    }
+}
+
+/**
+The distance given to tools::range_color for the least dense cells.
+ */
+const RAMP_FALLOFF: i32 = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_weights_neighbours() {
+        let heatmap = Heatmap::new().cell(1).radius(1);
+        let grid = heatmap.grid(&[coordinate!(1, 1)], coordinate!(), coordinate!(3, 3));
+        assert_eq!(grid[4], 1.0);
+        assert_eq!(grid[1], 0.5);
+        assert!(grid[0] > 0.0 && grid[0] < grid[1]);
+    }
+
+    #[test]
+    fn test_grid_ignores_outside() {
+        let heatmap = Heatmap::new().cell(2).radius(0);
+        let positions = Coordinate::from_list(&[(-1, 0), (4, 4), (1, 1)]);
+        let grid = heatmap.grid(&positions, coordinate!(), coordinate!(4, 4));
+        assert_eq!(grid.iter().sum::<f64>(), 1.0);
+    }
+
+    #[test]
+    fn test_grid_offset() {
+        let heatmap = Heatmap::new().cell(2).radius(0);
+        let grid = heatmap.grid(&[coordinate!(-1, -1)], coordinate!(3, 3), coordinate!(4, 4));
+        assert_eq!(grid, vec![0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_ramp_darkens() {
+        let heatmap = Heatmap::new();
+        let low = heatmap.ramp(0.25);
+        let high = heatmap.ramp(0.75);
+        assert!(low[0] <= high[0] && low[3] < high[3]);
+    }
+
+    #[test]
+    fn test_draw_leaves_empty_cells() {
+        let heatmap = Heatmap::new().cell(2).radius(0);
+        let mut image = IW::from(gen_canvas(8, 8));
+        heatmap.draw(&mut image, &[coordinate!(1, 1)], coordinate!());
+        assert_eq!(image.image().get_pixel(0, 0), &heatmap.color);
+        assert_eq!(image.image().get_pixel(7, 7)[3], 0);
+    }
+
+    #[test]
+    fn test_draw_blends_with_background() {
+        let heatmap = Heatmap::new()
+            .cell(2)
+            .radius(0)
+            .color(image::Rgba([255, 0, 0, 128]));
+        let blue = image::Rgba([0, 0, 255, 255]);
+        let mut image = IW::from(image::RgbaImage::from_pixel(4, 4, blue));
+        heatmap.draw(&mut image, &[coordinate!(1, 1)], coordinate!());
+        let pixel = image.image().get_pixel(0, 0);
+        assert!(pixel[0] > 0 && pixel[2] > 0);
+        assert_eq!(image.image().get_pixel(3, 3), &blue);
+    }
+}
@@ -0,0 +1,168 @@
+/*!
+Records where elements are drawn, so that rendered images can be made clickable.
+ */
+
+use super::*;
+use std::fmt::Write;
+
+/**
+The pixels a Node was drawn on, along with the hash of the Node and the layer and z-index it was drawn on.
+
+Edges are not part of the Hitbox, only the shape or sprite of the Node.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+let node = Node::new("A", Coordinate::new(0, 0));
+let map = Map::new().map(&[node]);
+let hitbox = map.hitboxes()[0];
+assert_eq!(hitbox.hash, node.hash());
+assert!(hitbox.contains(node.geo + map.offset()));
+```
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hitbox {
+    pub hash: u64,
+    pub rect: Rect,
+    pub layer: Layer,
+    pub z: i32,
+}
+
+impl Hitbox {
+    /**
+    Returns true if the pixel is within the Hitbox.
+     */
+    pub fn contains(&self, pixel: Coordinate) -> bool {
+        self.rect.contains(&pixel)
+    }
+}
+
+/**
+Returns an HTML image map with an area for every Hitbox, to be used with an img element's usemap attribute.
+
+Each area links to the hash of its Node as a fragment, and the areas on top are listed first,
+since browsers pick the first area containing the pixel clicked.
+The Hitboxes are expected in the order of Map::hitboxes, with the one on top last.
+
+
+## Examples
+
+```
+# use pathtracer::{map::hitbox, *};
+let node = Node::new("A", Coordinate::new(0, 0));
+let map = Map::new().map(&[node]);
+let html = hitbox::html(map.hitboxes(), "network");
+assert!(html.starts_with("<map name=\"network\">"));
+assert!(html.contains(&format!("href=\"#{}\"", node.hash())));
+```
+ */
+pub fn html(hitboxes: &[Hitbox], name: &str) -> String {
+    let mut html = format!("<map name=\"{}\">\n", escape(name));
+    for hitbox in hitboxes.iter().rev() {
+        let (min, max) = (hitbox.rect.min, hitbox.rect.max);
+        // Area coordinates are the edges of the rectangle, while the Rect includes its maximum pixel.
+        let _ = writeln!(
+            html,
+            "  <area shape=\"rect\" coords=\"{},{},{},{}\" href=\"#{}\" alt=\"{}\" data-hash=\"{}\">",
+            min.x,
+            min.y,
+            max.x + 1,
+            max.y + 1,
+            hitbox.hash,
+            hitbox.hash,
+            hitbox.hash
+        );
+    }
+    html.push_str("</map>\n");
+    html
+}
+
+/**
+Returns the Hitboxes as a JSON array, in the order they are given.
+
+Hashes are written as strings, since they do not fit in the numbers of JavaScript.
+The minimum and maximum are both pixels within the Hitbox.
+
+
+## Examples
+
+```
+# use pathtracer::{map::hitbox, *};
+let node = Node::new("A", Coordinate::new(0, 0));
+let map = Map::new().map(&[node]);
+let json = hitbox::json(map.hitboxes());
+assert!(json.starts_with(&format!("[{{\"hash\":\"{}\",", node.hash())));
+```
+ */
+pub fn json(hitboxes: &[Hitbox]) -> String {
+    let entries = hitboxes
+        .iter()
+        .map(|hitbox| {
+            let (min, max) = (hitbox.rect.min, hitbox.rect.max);
+            format!(
+                "{{\"hash\":\"{}\",\"min\":[{},{}],\"max\":[{},{}]}}",
+                hitbox.hash, min.x, min.y, max.x, max.y
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", entries.join(","))
+}
+
+/**
+Escapes the characters which can not be used as is in an HTML attribute.
+ */
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '"' => "&quot;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hitbox(hash: u64, min: (i32, i32), max: (i32, i32)) -> Hitbox {
+        Hitbox {
+            hash,
+            rect: Rect::new(coordinate!(min.0, min.1), coordinate!(max.0, max.1)),
+            layer: Layer::Nodes,
+            z: 0,
+        }
+    }
+
+    #[test]
+    fn test_html_lists_last_drawn_first() {
+        let html = html(
+            &[hitbox(1, (0, 0), (4, 4)), hitbox(2, (2, 2), (9, 9))],
+            "a\"b",
+        );
+        let lines = html.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "<map name=\"a&quot;b\">");
+        assert!(lines[1].contains("coords=\"2,2,10,10\" href=\"#2\""));
+        assert!(lines[2].contains("coords=\"0,0,5,5\" href=\"#1\""));
+        assert_eq!(lines[3], "</map>");
+    }
+
+    #[test]
+    fn test_json() {
+        let json = json(&[hitbox(u64::MAX, (0, 1), (2, 3)), hitbox(7, (4, 4), (4, 4))]);
+        assert_eq!(
+            json,
+            "[{\"hash\":\"18446744073709551615\",\"min\":[0,1],\"max\":[2,3]},\
+             {\"hash\":\"7\",\"min\":[4,4],\"max\":[4,4]}]"
+        );
+    }
+
+    #[test]
+    fn test_json_empty() {
+        assert_eq!(json(&[]), "[]");
+    }
+}
@@ -9,49 +9,104 @@ use image::Rgba;
 use std::cmp;
 
 pub mod gif;
+pub mod heatmap;
+pub mod hitbox;
 pub mod network;
+pub mod png;
+pub mod timeline;
+pub mod walk;
 
 /**
 Returns the underlaying image used for the Map struct.
 */
 pub fn gen_map<T: Location + Draw + MinMax>(
     list: &[T],
-) -> (image::ImageBuffer<Rgba<u8>, Vec<u8>>, (Coordinate)) {
-    let (min, max) = min_max(&list);
-    let diff = max - min;
-    let add = Coordinate::new(-min.x, -min.y);
-    let image = gen_canvas(diff.x as u32, diff.y as u32);
+) -> (image::ImageBuffer<Rgba<u8>, Vec<u8>>, Coordinate) {
+    let rect = min_max(&list);
+    let add = Coordinate::new(-rect.min.x, -rect.min.y);
+    let image = gen_canvas(rect.width() as u32, rect.height() as u32);
     (image, add)
 }
 
 /**
-Finds the min and max of a list and returns (min, max).
+Returns a Map for every frame, all sharing the same canvas so elements do not shift between frames.
+*/
+pub fn gen_frames<T: Location + Draw + Hash + MinMax + Clone>(frames: &[Vec<T>]) -> Vec<Map> {
+    gen_frames_with(frames.len(), |i| frames[i].clone()).collect()
+}
+
+/**
+Maps the elements of each frame as they are needed, all sharing the same canvas so elements do not shift between frames.
+
+The elements of every frame are generated once up front to size the canvas, and again when the frame is mapped,
+so only one frame is held in memory at a time.
+
+
+## Examples
 
-the min and max use the size of the Draw trait to enlarge the are the min, max occupy.
+```
+# use pathtracer::*;
+let mut maps = map::gen_frames_with(3, |i| vec![node!(i as i32 * 10, 0)]);
+let first = maps.next().unwrap().consume();
+assert_eq!(maps.count(), 2);
+assert!(first.dimensions().x > 20);
+```
 */
-fn min_max<T: Location + Draw + MinMax>(list: &[T]) -> (Coordinate, Coordinate) {
-    let mut size: i16 = consts::DEFAULT_SIZE as i16;
-    let mut min = coordinate!();
-    let mut max = coordinate!();
+pub fn gen_frames_with<T, F>(frames: usize, frame: F) -> impl Iterator<Item = Map>
+where
+    T: Location + Draw + Hash + MinMax,
+    F: Fn(usize) -> Vec<T>,
+{
+    let (size, rect) = (0..frames)
+        .map(|i| extent(&frame(i)))
+        .fold(extent::<T>(&[]), |acc, (size, rect)| {
+            (cmp::max(acc.0, size), acc.1.union(rect))
+        });
+    let rect = rect.expand(size / 4);
+    let add = Coordinate::new(-rect.min.x, -rect.min.y);
 
-    for item in list {
-        size = cmp::max(size, item.size() as i16);
-        let (imin, imax) = item.min_max();
+    (0..frames).map(move |i| {
+        Map {
+            image: Some(IW::layered(gen_canvas(
+                rect.width() as u32,
+                rect.height() as u32,
+            ))),
+            add,
+            ..Map::default()
+        }
+        .map(&frame(i))
+    })
+}
+
+/**
+Finds the Rect covering all the elements in a list.
 
-        max.x = cmp::max(max.x, imax.x);
-        min.x = cmp::min(min.x, imin.x);
-        max.y = cmp::max(max.y, imax.y);
-        min.y = cmp::min(min.y, imin.y);
+The Rect is enlarged using the size of the Draw trait.
+*/
+fn min_max<T: Location + Draw + MinMax>(list: &[T]) -> Rect {
+    let (size, rect) = extent(list);
+    rect.expand(size / 4)
+}
+
+/**
+Returns the largest size of the elements, and the Rect covering them before it is enlarged.
+*/
+fn extent<T: Location + Draw + MinMax>(list: &[T]) -> (i32, Rect) {
+    let mut size: i32 = consts::DEFAULT_SIZE as i32;
+    let mut rect = Rect::default();
+
+    for item in list {
+        size = cmp::max(size, item.size() as i32);
+        rect = rect.union(item.min_max());
     }
 
-    let size = coordinate!(size / 4);
-    (min - size, max + size)
+    (size, rect)
 }
 
 /**
 Generates a canvas from the image crate.
 */
-fn gen_canvas(w: u32, h: u32) -> image::ImageBuffer<Rgba<u8>, Vec<u8>> {
+pub fn gen_canvas(w: u32, h: u32) -> image::ImageBuffer<Rgba<u8>, Vec<u8>> {
     image::DynamicImage::new_rgba8(w, h).to_rgba()
 }
 
@@ -75,16 +130,55 @@ mod tests {
     #[test]
     fn test_min_max() {
         let nodes = Node::from_list(&[(-50, 50), (50, -50), (0, 25), (25, 0)]);
-        let (min, max) = min_max(&nodes);
-        assert_eq!(min, Coordinate::new(-55, -55));
-        assert_eq!(max, Coordinate::new(55, 55));
+        let rect = min_max(&nodes);
+        assert_eq!(rect.min, Coordinate::new(-55, -55));
+        assert_eq!(rect.max, Coordinate::new(55, 55));
     }
 
     #[test]
     fn test_min_max_2() {
         let nodes = Node::from_list(&[(-9999, 50), (50, -50), (0, 25), (9999, 0)]);
-        let (min, max) = min_max(&nodes);
-        assert_eq!(min, Coordinate::new(-10004, -55));
-        assert_eq!(max, Coordinate::new(10004, 55));
+        let rect = min_max(&nodes);
+        assert_eq!(rect.min, Coordinate::new(-10004, -55));
+        assert_eq!(rect.max, Coordinate::new(10004, 55));
+    }
+
+    #[test]
+    fn test_min_max_beyond_i16() {
+        let nodes = Node::from_list(&[(-99999, 50), (50, -50), (0, 25), (99999, 0)]);
+        let rect = min_max(&nodes);
+        assert_eq!(rect.min, Coordinate::new(-100004, -55));
+        assert_eq!(rect.max, Coordinate::new(100004, 55));
+    }
+
+    #[test]
+    fn test_gen_frames_with_matches_gen_frames() {
+        let frames = [
+            Node::from_list(&[(0, 0), (10, 0)]),
+            Node::from_list(&[(-40, 20)]),
+            Node::from_list(&[(90, -30), (5, 5)]),
+        ];
+        let lazy = gen_frames_with(frames.len(), |i| frames[i].clone()).collect::<Vec<_>>();
+        let eager = frames
+            .iter()
+            .map(|elements| {
+                let (image, add) = gen_map(&frames.concat());
+                Map {
+                    image: Some(IW::layered(image)),
+                    add,
+                    ..Map::default()
+                }
+                .map(elements)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(lazy.len(), eager.len());
+        for (a, b) in lazy.into_iter().zip(eager) {
+            assert_eq!(a.offset(), b.offset());
+            assert_eq!(
+                a.consume().image().clone().into_raw(),
+                b.consume().image().clone().into_raw()
+            );
+        }
     }
 }
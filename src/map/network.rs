@@ -70,11 +70,31 @@ pub fn path_shortest_leg<'a>(
     network: &'a Network<Node>,
     start: Node,
     goal: Node,
+) -> io::Result<Vec<Node>> {
+    path_shortest_leg_weighted(network, start, goal, &coordinate::distance)
+}
+
+/**
+Creates a path using the 'shortest leg' with a custom weight for each leg.
+
+The weight function receives the positions of the two nodes of a leg.
+
+
+## Errors
+
+The path could not be found.
+
+ */
+pub fn path_shortest_leg_weighted<'a>(
+    network: &'a Network<Node>,
+    start: Node,
+    goal: Node,
+    weight: &Fn(Coordinate, Coordinate) -> u32,
 ) -> io::Result<Vec<Node>> {
     // Create a new Branch-off path.
     let format = |mut nodes: Vec<Node>, link: &HL, acc: u32| -> WNodes {
         let node = network.hash_map[link.t as usize % consts::NETWORK_REM].unwrap();
-        let weight = acc + coordinate::distance(nodes.first().unwrap().geo, node.geo);
+        let weight = acc + weight(nodes.first().unwrap().geo, node.geo);
         nodes.insert(0, node);
         WNodes { weight, nodes }
     };
@@ -171,6 +191,19 @@ mod tests {
         assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn weighted_path_matches_default_weight() {
+        let net = network();
+        let path = path_shortest_leg_weighted(
+            &net,
+            get(&net, "D").unwrap(),
+            get(&net, "A").unwrap(),
+            &coordinate::distance,
+        )
+        .unwrap();
+        assert_eq!(path.len(), 4);
+    }
+
     #[test]
     fn valid_gets() {
         let network = network();
@@ -0,0 +1,392 @@
+/*!
+Png based animation sinks.
+
+Unlike Gifs, png images are not limited to 256 colors, so the shading of Nodes is kept intact.
+*/
+
+use super::super::*;
+use ::png::{chunk, BitDepth, ColorType, Encoder, HasParameters};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/**
+An animated png, written when it is finished or dropped.
+
+The Apng can be written to any std::io::Write, it defaults to a File.
+
+Every frame is kept in memory in compressed form until then,
+since the number of frames is stored before the first frame.
+
+
+## Examples
+
+```
+# use pathtracer::{map::png::Apng, *};
+# fn main() -> std::io::Result<()> {
+let nodes = Node::from_list(&[(10, 10), (30, 30)]);
+let mut apng = Apng::new("/tmp/example.png", 50, 50)?;
+apng.push(Map::new().map(&nodes))?;
+apng.push_frame_delay(&Map::new().map(&nodes[..1]).consume(), 50)?;
+assert_eq!(apng.frames(), 2);
+apng.finish()
+# }
+```
+*/
+pub struct Apng<W: Write = File> {
+    writer: W,
+    width: u32,
+    height: u32,
+    delay: u16,
+    repeat: u32,
+    frames: Vec<(u16, Vec<u8>)>,
+    finished: bool,
+}
+
+impl Apng<File> {
+    /**
+    Constructs an Apng and creates the file it is written to.
+
+
+    ## Errors
+
+    If the file can not be created.
+    */
+    pub fn new(filename: &str, width: u32, height: u32) -> Result<Self, io::Error> {
+        Ok(Apng::from_writer(File::create(filename)?, width, height))
+    }
+}
+
+impl<W: Write> Apng<W> {
+    /**
+    Constructs an Apng that is encoded into the writer.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::png::Apng, *};
+    # fn main() -> std::io::Result<()> {
+    let mut bytes = Vec::new();
+    let mut apng = Apng::from_writer(&mut bytes, 20, 20);
+    apng.push(Map::new().map(&[node!(5, 5)]))?;
+    apng.finish()?;
+    drop(apng);
+    assert_eq!(&bytes[1..4], b"PNG");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_writer(writer: W, width: u32, height: u32) -> Self {
+        Apng {
+            writer,
+            width,
+            height,
+            delay: consts::DEFAULT_GIF_DELAY,
+            repeat: 0,
+            frames: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /**
+    Sets the default delay between frames, in units of 10 ms.
+    */
+    pub fn delay(&mut self, delay: u16) {
+        self.delay = delay;
+    }
+
+    /**
+    Sets the number of times the animation is played, 0 loops infinitely which is the default.
+    */
+    pub fn repeat(&mut self, repeat: u32) {
+        self.repeat = repeat;
+    }
+
+    /**
+    Returns the number of frames that has been pushed.
+    */
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl<W: Write> Sink for Apng<W> {
+    fn push(&mut self, map: Map) -> Result<(), io::Error> {
+        let delay = self.delay;
+        self.push_frame_delay(&map.consume(), delay)
+    }
+
+    /**
+    Compresses the frame, cropping or padding it to the size of the Apng.
+
+
+    ## Errors
+
+    If the Apng is already finished, or if the frame can not be compressed.
+    */
+    fn push_frame_delay(&mut self, image: &IW, delay: u16) -> Result<(), io::Error> {
+        if self.finished {
+            return Err(io::Error::other(
+                "frames can not be pushed once the animated png is finished",
+            ));
+        }
+        let pixels = fit(image, self.width, self.height);
+        let mut buffer = Vec::new();
+        {
+            let mut encoder = Encoder::new(&mut buffer, self.width, self.height);
+            encoder.set(ColorType::RGBA).set(BitDepth::Eight);
+            encoder.write_header()?.write_image_data(&pixels)?;
+        }
+        self.frames.push((delay, image_data(&buffer)));
+        Ok(())
+    }
+
+    /**
+    Writes every frame to the writer.
+
+
+    ## Errors
+
+    If no frames have been pushed, or if the writer fails.
+    */
+    fn finish(&mut self) -> Result<(), io::Error> {
+        if self.finished {
+            return Ok(());
+        }
+        if self.frames.is_empty() {
+            return Err(io::Error::other(
+                "an animated png requires at least one frame",
+            ));
+        }
+        self.finished = true;
+
+        let mut encoder = Encoder::new(&mut self.writer, self.width, self.height);
+        encoder.set(ColorType::RGBA).set(BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+
+        let mut actl = Vec::new();
+        actl.extend_from_slice(&(self.frames.len() as u32).to_be_bytes());
+        actl.extend_from_slice(&self.repeat.to_be_bytes());
+        writer.write_chunk(chunk::acTL, &actl)?;
+
+        let mut sequence: u32 = 0;
+        for (i, (delay, data)) in self.frames.iter().enumerate() {
+            let mut fctl = Vec::new();
+            for value in [sequence, self.width, self.height, 0, 0].iter() {
+                fctl.extend_from_slice(&value.to_be_bytes());
+            }
+            fctl.extend_from_slice(&delay.to_be_bytes());
+            fctl.extend_from_slice(&100u16.to_be_bytes());
+            // Frames are not disposed, and replace the previous frame entirely.
+            fctl.extend_from_slice(&[0, 0]);
+            writer.write_chunk(chunk::fcTL, &fctl)?;
+            sequence += 1;
+
+            if i == 0 {
+                writer.write_chunk(chunk::IDAT, data)?;
+            } else {
+                let mut fdat = sequence.to_be_bytes().to_vec();
+                fdat.extend_from_slice(data);
+                writer.write_chunk(chunk::fdAT, &fdat)?;
+                sequence += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for Apng<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/**
+Saves every frame as a numbered png image in a directory.
+
+Frame delays are not stored, the frames are only numbered in the order they are pushed.
+
+
+## Examples
+
+```
+# use pathtracer::{map::png::Sequence, *};
+# use std::path::Path;
+# fn main() -> std::io::Result<()> {
+let mut sequence = Sequence::new(Path::new("/tmp/sequence"))?;
+sequence.push(Map::new().map(&Node::from_list(&[(0, 0), (10, 10)])))?;
+assert!(Path::new("/tmp/sequence/00000.png").exists());
+# Ok(())
+# }
+```
+*/
+pub struct Sequence {
+    dir: PathBuf,
+    frames: usize,
+}
+
+impl Sequence {
+    /**
+    Constructs a Sequence saving to the directory, which is created if needed.
+
+
+    ## Errors
+
+    If the directory can not be created.
+    */
+    pub fn new(dir: &Path) -> Result<Self, io::Error> {
+        fs::create_dir_all(dir)?;
+        Ok(Sequence {
+            dir: dir.to_path_buf(),
+            frames: 0,
+        })
+    }
+
+    /**
+    Returns the number of frames that has been saved.
+    */
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+impl Sink for Sequence {
+    fn push(&mut self, map: Map) -> Result<(), io::Error> {
+        self.push_frame_delay(&map.consume(), 0)
+    }
+
+    fn push_frame_delay(&mut self, image: &IW, _delay: u16) -> Result<(), io::Error> {
+        let path = self.dir.join(format!("{:05}.png", self.frames));
+        image.image().save(path)?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+/**
+Returns the rgba pixels of the image, cropped or padded with transparent pixels to the size.
+*/
+fn fit(image: &IW, width: u32, height: u32) -> Vec<u8> {
+    let img = image.image();
+    let mut pixels = vec![0; (width * height * 4) as usize];
+    for (x, y, pix) in img.enumerate_pixels() {
+        if x < width && y < height {
+            let i = ((y * width + x) * 4) as usize;
+            pixels[i..i + 4].copy_from_slice(&pix.data);
+        }
+    }
+    pixels
+}
+
+/**
+Returns the contents of the IDAT chunks in an encoded png.
+*/
+fn image_data(png: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    // Skips the png signature.
+    let mut i = 8;
+    while i + 8 <= png.len() {
+        let length = u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]) as usize;
+        let start = i + 8;
+        if png[i + 4..start] == chunk::IDAT {
+            data.extend_from_slice(&png[start..start + length]);
+        }
+        // Length, type, data and crc.
+        i = start + length + 4;
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn chunks(path: &str) -> Vec<[u8; 4]> {
+        let mut png = Vec::new();
+        File::open(path).unwrap().read_to_end(&mut png).unwrap();
+        let mut result = Vec::new();
+        let mut i = 8;
+        while i + 8 <= png.len() {
+            let length = u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]);
+            result.push([png[i + 4], png[i + 5], png[i + 6], png[i + 7]]);
+            i += 12 + length as usize;
+        }
+        result
+    }
+
+    #[test]
+    fn test_fit_pads_and_crops() {
+        let image = Map::new()
+            .map(&Node::from_list(&[(0, 0), (20, 20)]))
+            .consume();
+        assert_eq!(fit(&image, 5, 5).len(), 100);
+        assert_eq!(fit(&image, 100, 100).len(), 40_000);
+    }
+
+    #[test]
+    fn test_apng_chunks() -> std::io::Result<()> {
+        let path = "/tmp/test_apng.png";
+        let nodes = Node::from_list(&[(0, 0), (20, 20)]);
+        {
+            let mut apng = Apng::new(path, 30, 30)?;
+            for _ in 0..3 {
+                apng.push(Map::new().map(&nodes))?;
+            }
+        }
+
+        let chunks = chunks(path);
+        assert_eq!(chunks[0], chunk::IHDR);
+        assert_eq!(chunks[1], chunk::acTL);
+        assert_eq!(chunks.iter().filter(|&&c| c == chunk::fcTL).count(), 3);
+        assert_eq!(chunks.iter().filter(|&&c| c == chunk::fdAT).count(), 2);
+        assert_eq!(chunks.last(), Some(&chunk::IEND));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apng_readable_as_png() -> std::io::Result<()> {
+        let path = "/tmp/test_apng_readable.png";
+        {
+            let mut apng = Apng::new(path, 40, 20)?;
+            apng.push(Map::new().map(&Node::from_list(&[(0, 0), (10, 10)])))?;
+            apng.finish()?;
+        }
+        let image = image::open(path).unwrap();
+        assert_eq!(image.to_rgba().dimensions(), (40, 20));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apng_without_frames() -> std::io::Result<()> {
+        let mut apng = Apng::new("/tmp/test_apng_empty.png", 10, 10)?;
+        assert!(apng.finish().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_apng_push_after_finish() -> std::io::Result<()> {
+        let mut apng = Apng::from_writer(Vec::new(), 10, 10);
+        apng.push(Map::new().map(&[node!(5, 5)]))?;
+        apng.finish()?;
+        assert!(apng.push(Map::new().map(&[node!(5, 5)])).is_err());
+        assert_eq!(apng.frames(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequence_numbering() -> std::io::Result<()> {
+        let dir = Path::new("/tmp/test_sequence");
+        let mut sequence = Sequence::new(dir)?;
+        let nodes = Node::from_list(&[(0, 0), (10, 10)]);
+        for _ in 0..3 {
+            sequence.push(Map::new().map(&nodes))?;
+        }
+        assert_eq!(sequence.frames(), 3);
+        assert!(dir.join("00002.png").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,492 @@
+/*!
+Keyframe animations of Nodes over time.
+
+A Track moves a single Node between keyframes, and a Timeline plays Tracks in parallel or in sequence.
+ */
+
+use super::{png::Sequence, *};
+use std::{io, path::Path};
+
+/**
+Decides how a value changes between two keyframes.
+
+
+## Linear
+
+Changes at a constant speed.
+
+
+## EaseInOut
+
+Starts slow, speeds up, and slows down before reaching the keyframe.
+
+
+## Bounce
+
+Reaches the keyframe quickly and bounces against it before settling.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseInOut,
+    Bounce,
+}
+
+impl Easing {
+    /**
+    Maps the progress between two keyframes, from 0 to 1, to how far the value has changed.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::timeline::Easing;
+    for easing in [Easing::Linear, Easing::EaseInOut, Easing::Bounce].iter() {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+    assert!(Easing::EaseInOut.apply(0.25) < Easing::Linear.apply(0.25));
+    ```
+     */
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::Bounce => {
+                let (n, d) = (7.5625, 2.75);
+                if t < 1.0 / d {
+                    n * t * t
+                } else if t < 2.0 / d {
+                    let t = t - 1.5 / d;
+                    n * t * t + 0.75
+                } else if t < 2.5 / d {
+                    let t = t - 2.25 / d;
+                    n * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d;
+                    n * t * t + 0.984_375
+                }
+            }
+        }
+    }
+}
+
+/**
+The state of a Node at a point in time, in seconds from the start of its Track.
+
+Properties left as None keep changing towards the next keyframe that sets them.
+The easing is used for the changes leading up to the keyframe.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Keyframe {
+    pub time: f64,
+    pub geo: Option<Coordinate>,
+    pub color: Option<Rgba<u8>>,
+    pub radius: Option<u32>,
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    /**
+    Constructs a Keyframe that changes no properties.
+     */
+    pub fn new(time: f64) -> Self {
+        Keyframe {
+            time,
+            geo: None,
+            color: None,
+            radius: None,
+            easing: Easing::default(),
+        }
+    }
+
+    /**
+    Sets the position of the Node at the keyframe.
+     */
+    pub fn geo(mut self, geo: Coordinate) -> Self {
+        self.geo = Some(geo);
+        self
+    }
+
+    /**
+    Sets the color of the Node at the keyframe.
+     */
+    pub fn color(mut self, color: Rgba<u8>) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /**
+    Sets the radius of the Node at the keyframe.
+     */
+    pub fn radius(mut self, radius: u32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /**
+    Sets the easing used when changing towards the keyframe.
+     */
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/**
+Animates a single Node between a set of Keyframes.
+
+The Node itself is the state at the start of the Track.
+
+
+## Examples
+
+```
+# use pathtracer::{map::timeline::*, *};
+let track = Track::new(node!(0, 0))
+    .key(Keyframe::new(1.0).geo(coordinate!(100, 0)))
+    .key(Keyframe::new(2.0).geo(coordinate!(100, 50)).easing(Easing::Bounce));
+
+assert_eq!(track.duration(), 2.0);
+assert_eq!(track.node_at(0.5).geo, coordinate!(50, 0));
+assert_eq!(track.node_at(5.0).geo, coordinate!(100, 50));
+```
+ */
+#[derive(Debug, Clone)]
+pub struct Track {
+    node: Node,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    /**
+    Constructs a Track without any Keyframes.
+     */
+    pub fn new(node: Node) -> Self {
+        Track {
+            node,
+            keyframes: Vec::new(),
+        }
+    }
+
+    /**
+    Adds a Keyframe to the Track, keyframes are kept sorted by time.
+     */
+    pub fn key(mut self, keyframe: Keyframe) -> Self {
+        let i = self
+            .keyframes
+            .iter()
+            .position(|k| k.time > keyframe.time)
+            .unwrap_or(self.keyframes.len());
+        self.keyframes.insert(i, keyframe);
+        self
+    }
+
+    /**
+    Returns the time of the last Keyframe.
+     */
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |k| k.time.max(0.0))
+    }
+
+    /**
+    Returns the Node as it is at the given time.
+     */
+    pub fn node_at(&self, time: f64) -> Node {
+        let mut node = self.node;
+        node.geo = self.tween(time, node.geo, &|k| k.geo, &|a, b, t| a.lerp(b, t));
+        node.color = self.tween(time, node.color, &|k| k.color, &|a, b, t| {
+            let mut c = a;
+            for i in 0..4 {
+                c.data[i] = lerp(f64::from(a.data[i]), f64::from(b.data[i]), t) as u8;
+            }
+            c
+        });
+        if self.keyframes.iter().any(|k| k.radius.is_some()) {
+            let radius = self.tween(time, node.size(), &|k| k.radius, &|a, b, t| {
+                lerp(f64::from(a), f64::from(b), t) as u32
+            });
+            node.radius = Some(radius);
+        }
+        node
+    }
+
+    /**
+    Interpolates a single property between the keyframes surrounding the time.
+     */
+    fn tween<V: Copy>(
+        &self,
+        time: f64,
+        start: V,
+        get: &dyn Fn(&Keyframe) -> Option<V>,
+        lerp: &dyn Fn(V, V, f64) -> V,
+    ) -> V {
+        let mut prev = (0.0, start);
+        for k in self.keyframes.iter() {
+            if let Some(value) = get(k) {
+                if k.time > time {
+                    let t = (time - prev.0) / (k.time - prev.0);
+                    return lerp(prev.1, value, k.easing.apply(t));
+                }
+                prev = (k.time, value);
+            }
+        }
+        prev.1
+    }
+}
+
+/**
+Plays a set of Tracks, each starting at a given time.
+
+
+## Examples
+
+```
+# use pathtracer::{map::{gif::Gif, timeline::*}, *};
+# fn main() -> std::io::Result<()> {
+let a = Track::new(node!(0, 0)).key(Keyframe::new(1.0).geo(coordinate!(40, 0)));
+let b = Track::new(node!(10, 10)).key(Keyframe::new(1.0).radius(10));
+let c = Track::new(node!(40, 20)).key(Keyframe::new(0.5).color(image::Rgba([255, 0, 0, 255])));
+
+let mut timeline = Timeline::new();
+timeline.parallel(a);
+timeline.parallel(b);
+timeline.sequential(c);
+assert_eq!(timeline.duration(), 1.5);
+
+let mut gif = Gif::new("/tmp/timeline.gif", 60, 40);
+timeline.render(&mut gif, 10)?;
+assert_eq!(gif.frames(), 16);
+# Ok(())
+# }
+```
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    tracks: Vec<(f64, Track)>,
+}
+
+impl Timeline {
+    /**
+    Constructs an empty Timeline.
+     */
+    pub fn new() -> Self {
+        Timeline::default()
+    }
+
+    /**
+    Adds a Track starting at the same time as the previously added Track.
+     */
+    pub fn parallel(&mut self, track: Track) {
+        let start = self.tracks.last().map_or(0.0, |x| x.0);
+        self.tracks.push((start, track));
+    }
+
+    /**
+    Adds a Track starting once every previously added Track has finished.
+     */
+    pub fn sequential(&mut self, track: Track) {
+        let start = self.duration();
+        self.tracks.push((start, track));
+    }
+
+    /**
+    Returns the time in seconds when the last Track finishes.
+     */
+    pub fn duration(&self) -> f64 {
+        self.tracks
+            .iter()
+            .fold(0.0, |acc, (start, track)| acc.max(start + track.duration()))
+    }
+
+    /**
+    Returns every Node as it is at the given time.
+
+    Nodes are shown in their first state before their Track starts, and in their last state after it ends.
+     */
+    pub fn nodes_at(&self, time: f64) -> Vec<Node> {
+        self.tracks
+            .iter()
+            .map(|(start, track)| track.node_at(time - start))
+            .collect()
+    }
+
+    /**
+    Returns the number of frames needed to show the entire Timeline at the frame rate.
+     */
+    pub fn frames(&self, fps: u32) -> usize {
+        // Avoids an extra frame when adding up times gives rounding errors.
+        (self.duration() * f64::from(fps) - 1e-9).ceil().max(0.0) as usize + 1
+    }
+
+    /**
+    Returns a Map for every frame, all sharing the same canvas so Nodes do not shift between frames.
+
+
+    ## Panics
+
+    If the frame rate is zero.
+     */
+    pub fn maps(&self, fps: u32) -> Vec<Map> {
+        assert!(fps > 0, "frame rate must be above zero");
+        self.gen_maps(fps).collect()
+    }
+
+    /**
+    Pushes every frame on to the Sink at the frame rate, mapping one frame at a time.
+
+    Delays are measured in units of 10 ms, so frame rates above 100 are not shown correctly.
+
+
+    ## Errors
+
+    If the frame rate is zero, or the Sink fails to write a frame.
+     */
+    pub fn render<S: Sink>(&self, sink: &mut S, fps: u32) -> Result<(), io::Error> {
+        if fps == 0 {
+            return Err(io::Error::other("frame rate must be above zero"));
+        }
+
+        let delay = (100.0 / f64::from(fps)).round() as u16;
+        for map in self.gen_maps(fps) {
+            sink.push_frame_delay(&map.consume(), delay)?;
+        }
+        Ok(())
+    }
+
+    /**
+    Saves every frame as a numbered png image in the directory, which is created if needed.
+
+
+    ## Errors
+
+    If the directory can not be created, or an image can not be saved.
+     */
+    pub fn save(&self, dir: &Path, fps: u32) -> Result<(), io::Error> {
+        self.render(&mut Sequence::new(dir)?, fps)
+    }
+
+    /**
+    Maps each frame as it is needed.
+     */
+    fn gen_maps(&self, fps: u32) -> impl Iterator<Item = Map> + '_ {
+        map::gen_frames_with(self.frames(fps), move |i| {
+            self.nodes_at(i as f64 / f64::from(fps))
+        })
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    (a + (b - a) * t).round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounce_settles() {
+        let mut last = 0.0;
+        for i in 0..=100 {
+            let t = Easing::Bounce.apply(f64::from(i) / 100.0);
+            assert!((0.0..=1.0).contains(&t));
+            last = t;
+        }
+        assert_eq!(last, 1.0);
+    }
+
+    #[test]
+    fn test_ease_in_out_symmetric() {
+        let a = Easing::EaseInOut.apply(0.2);
+        let b = Easing::EaseInOut.apply(0.8);
+        assert!((a + b - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_keyframes_sorted() {
+        let track = Track::new(node!(0, 0))
+            .key(Keyframe::new(2.0).geo(coordinate!(20, 0)))
+            .key(Keyframe::new(1.0).geo(coordinate!(10, 0)));
+        assert_eq!(track.node_at(1.0).geo, coordinate!(10, 0));
+        assert_eq!(track.node_at(1.5).geo, coordinate!(15, 0));
+    }
+
+    #[test]
+    fn test_properties_tween_separately() {
+        let red = Rgba([255, 0, 0, 255]);
+        let track = Track::new(node!(0, 0))
+            .key(Keyframe::new(1.0).geo(coordinate!(10, 0)))
+            .key(Keyframe::new(2.0).color(red).radius(20));
+
+        let node = track.node_at(1.0);
+        assert_eq!(node.geo, coordinate!(10, 0));
+        assert_eq!(node.radius, Some(12));
+        assert_eq!(track.node_at(2.0).color, red);
+    }
+
+    #[test]
+    fn test_radius_untouched_without_keyframes() {
+        let track = Track::new(node!(0, 0)).key(Keyframe::new(1.0).geo(coordinate!(5, 5)));
+        assert_eq!(track.node_at(0.5).radius, None);
+    }
+
+    #[test]
+    fn test_sequential_tracks() {
+        let a = Track::new(node!(0, 0)).key(Keyframe::new(1.0).geo(coordinate!(10, 0)));
+        let b = Track::new(node!(0, 10)).key(Keyframe::new(2.0).geo(coordinate!(10, 10)));
+
+        let mut timeline = Timeline::new();
+        timeline.parallel(a);
+        timeline.sequential(b);
+        assert_eq!(timeline.duration(), 3.0);
+
+        let nodes = timeline.nodes_at(2.0);
+        assert_eq!(nodes[0].geo, coordinate!(10, 0));
+        assert_eq!(nodes[1].geo, coordinate!(5, 10));
+    }
+
+    #[test]
+    fn test_maps_share_canvas() {
+        let track = Track::new(node!(0, 0)).key(Keyframe::new(1.0).geo(coordinate!(50, 50)));
+        let mut timeline = Timeline::new();
+        timeline.parallel(track);
+
+        let maps = timeline.maps(4);
+        assert_eq!(maps.len(), 5);
+        let offset = maps[0].offset();
+        let dimensions = maps[0].clone().consume().dimensions();
+        for map in maps {
+            assert_eq!(map.offset(), offset);
+            assert_eq!(map.consume().dimensions(), dimensions);
+        }
+    }
+
+    #[test]
+    fn test_save_sequence() -> std::io::Result<()> {
+        let track = Track::new(node!(0, 0)).key(Keyframe::new(1.0).geo(coordinate!(20, 0)));
+        let mut timeline = Timeline::new();
+        timeline.parallel(track);
+
+        let dir = Path::new("/tmp/test_timeline");
+        timeline.save(dir, 2)?;
+        assert!(dir.join("00002.png").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_fps() {
+        let mut timeline = Timeline::new();
+        timeline.parallel(Track::new(node!(0, 0)));
+        let mut sequence = Sequence::new(Path::new("/tmp/test_timeline_zero_fps")).unwrap();
+        assert!(timeline.render(&mut sequence, 0).is_err());
+        assert_eq!(sequence.frames(), 0);
+    }
+}
@@ -0,0 +1,286 @@
+/*!
+Animates a walker travelling along a path of Nodes.
+
+The walker follows the edges between the Nodes as they are drawn, and leaves a trail on the edges it has visited.
+ */
+
+use super::*;
+use std::{
+    cmp,
+    io::{self, Error},
+};
+
+/**
+A route along a path of linked Nodes, rendered one frame at a time.
+
+
+## Examples
+
+```
+# use pathtracer::{map::{gif::Gif, walk::Walk}, *};
+# fn main() -> std::io::Result<()> {
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (40, 10), (80, 40)]));
+let path = Network::new(nodes.clone()).path("A", "C")?;
+
+let mut walk = Walk::new(&path)?;
+walk.speed(10);
+
+let mut gif = Gif::new("/tmp/walk.gif", 100, 60);
+walk.render(&mut gif, &nodes)?;
+assert_eq!(gif.frames() as usize, walk.frames());
+# Ok(())
+# }
+```
+ */
+#[derive(Clone, Debug)]
+pub struct Walk {
+    route: Vec<Coordinate>,
+    speed: usize,
+    pub walker: Node,
+    pub trail: Rgba<u8>,
+}
+
+impl Walk {
+    /**
+    Constructs a Walk following the edges between each Node in the path.
+
+    Each pair of Nodes must be linked in either direction.
+
+
+    ## Errors
+
+    If the path is empty, or if two following Nodes in the path are not linked.
+     */
+    pub fn new(path: &[Node]) -> Result<Self, io::Error> {
+        let first = path
+            .first()
+            .ok_or_else(|| Error::other("can not walk an empty path"))?;
+
+        let mut route = vec![first.geo];
+        for pair in path.windows(2) {
+            for c in edge(&pair[0], &pair[1])?
+                .into_iter()
+                .chain(Some(pair[1].geo))
+            {
+                if route.last() != Some(&c) {
+                    route.push(c);
+                }
+            }
+        }
+
+        let mut walker = Node::new("walker", first.geo);
+        walker.color = Rgba([255, 50, 50, 255]);
+        Ok(Walk {
+            route,
+            speed: 1,
+            walker,
+            trail: Rgba([255, 150, 50, 255]),
+        })
+    }
+
+    /**
+    Sets how many positions along the route the walker moves every frame.
+     */
+    pub fn speed(&mut self, speed: usize) {
+        self.speed = cmp::max(speed, 1);
+    }
+
+    /**
+    Returns every position the walker passes, in order.
+     */
+    pub fn route(&self) -> &[Coordinate] {
+        &self.route
+    }
+
+    /**
+    Returns the number of frames needed for the walker to reach the end of the route.
+     */
+    pub fn frames(&self) -> usize {
+        (self.route.len() - 1).div_ceil(self.speed) + 1
+    }
+
+    /**
+    Returns the position of the walker at the given frame.
+     */
+    pub fn position(&self, frame: usize) -> Coordinate {
+        self.route[cmp::min(frame * self.speed, self.route.len() - 1)]
+    }
+
+    /**
+    Maps the Nodes with the trail and the walker as they are at the given frame.
+     */
+    pub fn frame(&self, nodes: &[Node], frame: usize) -> Map {
+        let end = self.end(frame);
+        Map::new()
+            .map(nodes)
+            .map(&self.trail(0, end))
+            .map(&[self.walker_at(end)])
+    }
+
+    /**
+    Pushes every frame of the walk on to the Sink.
+
+    The trail is kept on a canvas between frames, so each frame only draws the part of the route walked since the last.
+
+
+    ## Errors
+
+    If the Sink fails to write a frame.
+     */
+    pub fn render<S: Sink>(&self, sink: &mut S, nodes: &[Node]) -> Result<(), io::Error> {
+        let mut canvas = Map::new().map(nodes);
+        let mut start = 0;
+        for i in 0..self.frames() {
+            let end = self.end(i);
+            canvas = canvas.map(&self.trail(start, end));
+            start = end + 1;
+            sink.push(canvas.clone().map(&[self.walker_at(end)]))?;
+        }
+        Ok(())
+    }
+
+    /**
+    Returns the index in the route the walker is at for the given frame.
+     */
+    fn end(&self, frame: usize) -> usize {
+        cmp::min(frame * self.speed, self.route.len() - 1)
+    }
+
+    /**
+    Returns the trail left on the route between the two indexes, including both.
+     */
+    fn trail(&self, start: usize, end: usize) -> Vec<Node> {
+        self.route[start..=end]
+            .iter()
+            .map(|&c| {
+                let mut node = self.walker;
+                node.geo = c;
+                node.color = self.trail;
+                node.radius = Some(u32::from(consts::DEFAULT_LINK_SIZE));
+                node
+            })
+            .collect()
+    }
+
+    /**
+    Returns the walker positioned at the index in the route.
+     */
+    fn walker_at(&self, index: usize) -> Node {
+        let mut walker = self.walker;
+        walker.geo = self.route[index];
+        walker
+    }
+}
+
+/**
+Returns the Coordinates of the edge linking two Nodes, ordered from a to b.
+ */
+fn edge(a: &Node, b: &Node) -> Result<Vec<Coordinate>, io::Error> {
+    let find = |from: &Node, to: &Node| {
+        from.links()
+            .iter()
+            .find(|l| l.is_connected() && l.t == to.hash)
+            .map(|l| l.plot())
+    };
+
+    if let Some(plot) = find(a, b) {
+        Ok(plot)
+    } else if let Some(mut plot) = find(b, a) {
+        plot.reverse();
+        Ok(plot)
+    } else {
+        Err(Error::other("nodes in the path are not linked"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gif::Gif, *};
+
+    fn nodes() -> Vec<Node> {
+        Node::linked_list(Node::from_list(&[(0, 0), (20, 0), (20, 20)]))
+    }
+
+    #[test]
+    fn test_empty_path() {
+        assert!(Walk::new(&[]).is_err());
+    }
+
+    #[test]
+    fn test_unlinked_path() {
+        let nodes = Node::from_list(&[(0, 0), (20, 0)]);
+        assert!(Walk::new(&nodes).is_err());
+    }
+
+    #[test]
+    fn test_route_follows_path() {
+        let nodes = nodes();
+        let walk = Walk::new(&nodes).unwrap();
+        assert_eq!(walk.route().first(), Some(&coordinate!(0, 0)));
+        assert!(walk.route().contains(&coordinate!(20, 0)));
+        assert_eq!(walk.position(walk.frames() - 1), coordinate!(20, 20));
+    }
+
+    #[test]
+    fn test_route_reversed_path() {
+        let mut nodes = nodes();
+        nodes.reverse();
+        let walk = Walk::new(&nodes).unwrap();
+        assert_eq!(walk.route().first(), Some(&coordinate!(20, 20)));
+        assert_eq!(walk.route().last(), Some(&coordinate!(0, 0)));
+    }
+
+    #[test]
+    fn test_speed() {
+        let mut walk = Walk::new(&nodes()).unwrap();
+        let slow = walk.frames();
+        walk.speed(5);
+        assert!(walk.frames() < slow);
+        assert_eq!(
+            walk.position(walk.frames() + 10),
+            walk.position(walk.frames() - 1)
+        );
+    }
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let nodes = nodes();
+        let mut walk = Walk::new(&nodes)?;
+        walk.speed(8);
+        let mut gif = Gif::new("/tmp/test_walk.gif", 30, 30);
+        walk.render(&mut gif, &nodes)?;
+        assert_eq!(gif.frames(), walk.frames());
+        Ok(())
+    }
+
+    struct Frames(Vec<Vec<u8>>);
+
+    impl Sink for Frames {
+        fn push(&mut self, map: Map) -> Result<(), io::Error> {
+            self.push_frame_delay(&map.consume(), 0)
+        }
+
+        fn push_frame_delay(&mut self, image: &IW, _delay: u16) -> Result<(), io::Error> {
+            self.0.push(image.image().clone().into_raw());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_render_matches_frames() -> std::io::Result<()> {
+        let nodes = nodes();
+        let mut walk = Walk::new(&nodes)?;
+        walk.speed(3);
+        let mut frames = Frames(Vec::new());
+        walk.render(&mut frames, &nodes)?;
+
+        assert_eq!(frames.0.len(), walk.frames());
+        for (i, rendered) in frames.0.iter().enumerate() {
+            assert_eq!(
+                rendered,
+                &walk.frame(&nodes, i).consume().image().clone().into_raw()
+            );
+        }
+        Ok(())
+    }
+}
@@ -74,8 +74,8 @@ pub fn from_file(path: &str) -> Result<Vec<Node>, io::Error> {
     Ok(contents.split('\n').fold(vec![], |mut acc, x| {
         let vals = x.split(',').collect::<Vec<_>>();
         let c = Coordinate::new(
-            vals[0].parse::<i16>().unwrap(),
-            vals[1].parse::<i16>().unwrap(),
+            vals[0].parse::<i32>().unwrap(),
+            vals[1].parse::<i32>().unwrap(),
         );
         acc.push(Node::new(x, c));
         acc
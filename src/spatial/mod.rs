@@ -0,0 +1,481 @@
+/*!
+Spatial indexing of located structures.
+
+Finds what is close to a point or inside an area without checking every element.
+ */
+
+use super::{consts, Coordinate, Location, Rect};
+use std::{
+    cmp::{max, min, Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+/**
+A quadtree over any structure that implements Location.
+
+Each quad is split in four once it holds more than consts::QUAD_CAPACITY elements.
+
+
+## Examples
+
+```
+# use pathtracer::{spatial::QuadTree, *};
+let nodes = Node::from_list(&[(0, 0), (10, 10), (50, 50), (100, 100)]);
+let tree = QuadTree::new(nodes);
+let nearest = tree.nearest(Coordinate::new(45, 45), 1);
+assert_eq!(nearest[0].geo, Coordinate::new(50, 50));
+```
+ */
+#[derive(Clone, Debug)]
+pub struct QuadTree<T: Location> {
+    items: Vec<T>,
+    root: Quad,
+}
+
+/**
+A node in the quadtree, covering the area of the Rect including its edges.
+ */
+#[derive(Clone, Debug)]
+struct Quad {
+    rect: Rect,
+    items: Vec<usize>,
+    children: Vec<Quad>,
+}
+
+/**
+Entry in the nearest search queue, ordered by the squared distance.
+ */
+struct Candidate<'a> {
+    distance: i64,
+    quad: Option<&'a Quad>,
+    item: usize,
+}
+
+impl<'a> PartialEq for Candidate<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for Candidate<'a> {}
+
+impl<'a> Ord for Candidate<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .cmp(&other.distance)
+            .then(self.quad.is_some().cmp(&other.quad.is_some()))
+            .then(self.item.cmp(&other.item))
+    }
+}
+
+impl<'a> PartialOrd for Candidate<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Quad {
+    fn new(rect: Rect) -> Self {
+        Quad {
+            rect,
+            items: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /**
+    Squared distance from the point to the closest position inside the quad.
+     */
+    fn distance(&self, c: Coordinate) -> i64 {
+        let (lo, hi) = (self.rect.min, self.rect.max);
+        let closest = Coordinate::new(max(lo.x, min(c.x, hi.x)), max(lo.y, min(c.y, hi.y)));
+        let d = c - closest;
+        d.dot(d)
+    }
+
+    fn insert<T: Location>(&mut self, index: usize, items: &[T]) {
+        if !self.children.is_empty() {
+            let c = items[index].position();
+            for child in self.children.iter_mut() {
+                if child.rect.contains(&c) {
+                    return child.insert(index, items);
+                }
+            }
+        }
+
+        self.items.push(index);
+        if self.items.len() > consts::QUAD_CAPACITY && self.children.is_empty() {
+            self.split(items);
+        }
+    }
+
+    fn split<T: Location>(&mut self, items: &[T]) {
+        // Quads of a single position can not be split any further.
+        if self.rect.min == self.rect.max {
+            return;
+        }
+
+        self.children = self.quarters();
+        for index in std::mem::take(&mut self.items) {
+            self.insert(index, items);
+        }
+    }
+
+    /**
+    The four quads the area is split in, with the middle in the first one.
+     */
+    fn quarters(&self) -> Vec<Quad> {
+        let (lo, hi) = (self.rect.min, self.rect.max);
+        let mid = coordinate!(lo.x + self.rect.width() / 2, lo.y + self.rect.height() / 2);
+        let quad = |min: Coordinate, max: Coordinate| Quad::new(Rect { min, max });
+        vec![
+            quad(lo, mid),
+            quad(coordinate!(mid.x + 1, lo.y), coordinate!(hi.x, mid.y)),
+            quad(coordinate!(lo.x, mid.y + 1), coordinate!(mid.x, hi.y)),
+            quad(coordinate!(mid.x + 1, mid.y + 1), hi),
+        ]
+    }
+
+    /**
+    Returns a quad of twice the size, extended toward the Coordinate, with this quad as one of its children.
+
+    Splitting the larger quad in four lands exactly on the edges of this quad,
+    so none of the elements need to be inserted again.
+     */
+    fn grow(self, c: Coordinate) -> Quad {
+        let (lo, hi) = (self.rect.min, self.rect.max);
+        let size = self.rect.size() + coordinate!(1);
+        let (min_x, max_x) = if c.x < lo.x {
+            (lo.x - size.x, hi.x)
+        } else {
+            (lo.x, hi.x + size.x)
+        };
+        let (min_y, max_y) = if c.y < lo.y {
+            (lo.y - size.y, hi.y)
+        } else {
+            (lo.y, hi.y + size.y)
+        };
+
+        let mut parent = Quad::new(Rect::new(
+            coordinate!(min_x, min_y),
+            coordinate!(max_x, max_y),
+        ));
+        parent.children = parent.quarters();
+        let position = parent
+            .children
+            .iter()
+            .position(|child| child.rect == self.rect)
+            .unwrap();
+        parent.children[position] = self;
+        parent
+    }
+
+    fn within_rect(&self, rect: Rect, result: &mut Vec<usize>) {
+        if !self.rect.intersects(rect) {
+            return;
+        }
+        result.extend(self.items.iter());
+        for child in self.children.iter() {
+            child.within_rect(rect, result);
+        }
+    }
+}
+
+impl<T: Location> QuadTree<T> {
+    /**
+    Constructs a QuadTree covering all the given elements.
+     */
+    pub fn new(items: Vec<T>) -> Self {
+        let mut tree = QuadTree {
+            root: Quad::new(bounds(&items)),
+            items: Vec::new(),
+        };
+        for item in items {
+            tree.insert(item);
+        }
+        tree
+    }
+
+    /**
+    Adds an element to the tree.
+
+    If the element is outside the area of the tree, the area is doubled toward it until it is covered,
+    keeping the existing quads as they are.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{spatial::QuadTree, *};
+    let mut tree = QuadTree::new(Node::from_list(&[(0, 0)]));
+    tree.insert(Node::new("B", Coordinate::new(-500, 500)));
+    assert_eq!(tree.len(), 2);
+    ```
+     */
+    pub fn insert(&mut self, item: T) {
+        let c = item.position();
+        self.items.push(item);
+
+        while !self.root.rect.contains(&c) {
+            let root = std::mem::replace(&mut self.root, Quad::new(Rect::default()));
+            self.root = root.grow(c);
+        }
+        let index = self.items.len() - 1;
+        self.root.insert(index, &self.items);
+    }
+
+    /**
+    Returns the number of elements in the tree.
+     */
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /**
+    Returns true if the tree has no elements.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /**
+    Retrieves all the elements in the tree, in the order they were inserted.
+     */
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /**
+    Returns up to k elements closest to the Coordinate, sorted with the closest first.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{spatial::QuadTree, *};
+    let tree = QuadTree::new(Node::from_list(&[(0, 0), (10, 0), (20, 0), (30, 0)]));
+    let nearest = tree.nearest(Coordinate::new(12, 0), 2);
+    assert_eq!(nearest[0].geo, Coordinate::new(10, 0));
+    assert_eq!(nearest[1].geo, Coordinate::new(20, 0));
+    ```
+     */
+    pub fn nearest(&self, c: Coordinate, k: usize) -> Vec<&T> {
+        let mut result = Vec::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse(Candidate {
+            distance: self.root.distance(c),
+            quad: Some(&self.root),
+            item: 0,
+        }));
+
+        while let Some(Reverse(candidate)) = queue.pop() {
+            if result.len() >= k {
+                break;
+            }
+
+            match candidate.quad {
+                Some(quad) => {
+                    for &item in quad.items.iter() {
+                        let d = self.items[item].position() - c;
+                        queue.push(Reverse(Candidate {
+                            distance: d.dot(d),
+                            quad: None,
+                            item,
+                        }));
+                    }
+                    for child in quad.children.iter() {
+                        queue.push(Reverse(Candidate {
+                            distance: child.distance(c),
+                            quad: Some(child),
+                            item: 0,
+                        }));
+                    }
+                }
+                None => result.push(&self.items[candidate.item]),
+            }
+        }
+        result
+    }
+
+    /**
+    Returns all the elements within the radius of the Coordinate.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{spatial::QuadTree, *};
+    let tree = QuadTree::new(Node::from_list(&[(0, 0), (3, 4), (20, 20)]));
+    assert_eq!(tree.within_radius(Coordinate::new(0, 0), 5).len(), 2);
+    ```
+     */
+    pub fn within_radius(&self, c: Coordinate, radius: u32) -> Vec<&T> {
+        let r = radius as i32;
+        let r2 = i64::from(radius) * i64::from(radius);
+        self.within_rect(Rect::new(c, c).expand(r))
+            .into_iter()
+            .filter(|item| {
+                let d = item.position() - c;
+                d.dot(d) <= r2
+            })
+            .collect()
+    }
+
+    /**
+    Returns all the elements inside the Rect, including its edges.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{spatial::QuadTree, *};
+    let tree = QuadTree::new(Node::from_list(&[(0, 0), (5, 5), (20, 20)]));
+    let found = tree.within_rect(Rect::new(Coordinate::new(0, 0), Coordinate::new(10, 10)));
+    assert_eq!(found.len(), 2);
+    ```
+     */
+    pub fn within_rect(&self, rect: Rect) -> Vec<&T> {
+        let mut indexes = Vec::new();
+        self.root.within_rect(rect, &mut indexes);
+        indexes.sort();
+        indexes
+            .into_iter()
+            .map(|i| &self.items[i])
+            .filter(|item| rect.contains(*item))
+            .collect()
+    }
+}
+
+/**
+Returns the Rect covering the positions of a list of elements.
+ */
+fn bounds<T: Location>(items: &[T]) -> Rect {
+    let first = items.first().map(|x| x.position()).unwrap_or_default();
+    items.iter().fold(Rect::new(first, first), |acc, item| {
+        let c = item.position();
+        acc.union(Rect::new(c, c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{coordinate, Node},
+        *,
+    };
+
+    fn grid() -> Vec<Node> {
+        let mut list = Vec::new();
+        for x in -20..20 {
+            for y in -20..20 {
+                list.push((x * 5, y * 5));
+            }
+        }
+        Node::from_list(&list)
+    }
+
+    #[test]
+    fn test_empty() {
+        let tree: QuadTree<Coordinate> = QuadTree::new(Vec::new());
+        assert!(tree.is_empty());
+        assert!(tree.nearest(coordinate!(), 3).is_empty());
+        assert!(tree.within_radius(coordinate!(), 100).is_empty());
+    }
+
+    #[test]
+    fn test_split() {
+        let tree = QuadTree::new(grid());
+        assert_eq!(tree.len(), 1600);
+        assert!(!tree.root.children.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_positions() {
+        let tree = QuadTree::new(vec![coordinate!(7); 100]);
+        assert_eq!(tree.within_radius(coordinate!(7), 0).len(), 100);
+    }
+
+    #[test]
+    fn test_nearest_matches_scan() {
+        let nodes = grid();
+        let tree = QuadTree::new(nodes.clone());
+        let c = coordinate!(13, -7);
+
+        let mut scan = nodes
+            .iter()
+            .map(|n| coordinate::distance(n.geo, c))
+            .collect::<Vec<_>>();
+        scan.sort();
+
+        let found = tree
+            .nearest(c, 10)
+            .iter()
+            .map(|n| coordinate::distance(n.geo, c))
+            .collect::<Vec<_>>();
+        assert_eq!(found, scan[..10].to_vec());
+    }
+
+    #[test]
+    fn test_within_radius_matches_scan() {
+        let nodes = grid();
+        let tree = QuadTree::new(nodes.clone());
+        let c = coordinate!(-31, 42);
+        let scan = nodes
+            .iter()
+            .filter(|n| (n.geo - c).length() <= 22.0)
+            .count();
+        assert_eq!(tree.within_radius(c, 22).len(), scan);
+    }
+
+    #[test]
+    fn test_within_rect_matches_scan() {
+        let nodes = grid();
+        let tree = QuadTree::new(nodes.clone());
+        let (min, max) = (coordinate!(-12, -40), coordinate!(33, 2));
+        let scan = nodes
+            .iter()
+            .filter(|n| {
+                n.geo.x >= min.x && n.geo.x <= max.x && n.geo.y >= min.y && n.geo.y <= max.y
+            })
+            .count();
+        assert_eq!(tree.within_rect(Rect { min, max }).len(), scan);
+    }
+
+    #[test]
+    fn test_insert_outside_bounds() {
+        let mut tree = QuadTree::new(grid());
+        tree.insert(Node::new("far", coordinate!(1000, 1000)));
+        let nearest = tree.nearest(coordinate!(900, 900), 1);
+        assert_eq!(nearest[0].geo, coordinate!(1000, 1000));
+    }
+
+    #[test]
+    fn test_insert_grows_root() {
+        fn find(quad: &Quad, rect: Rect) -> bool {
+            quad.rect == rect || quad.children.iter().any(|child| find(child, rect))
+        }
+
+        let mut tree = QuadTree::new(grid().into_iter().map(|n| n.geo).collect());
+        let old = tree.root.rect;
+        let far = coordinate!(-1000, 30);
+        tree.insert(far);
+
+        assert!(tree.root.rect.contains(&far));
+        assert_eq!(tree.root.rect.max.x, old.max.x);
+        assert!(find(&tree.root, old));
+
+        let c = coordinate!(-400, 0);
+        let mut scan = tree
+            .items()
+            .iter()
+            .map(|n| coordinate::distance(*n, c))
+            .collect::<Vec<_>>();
+        scan.sort();
+        let found = tree
+            .nearest(c, 5)
+            .iter()
+            .map(|n| coordinate::distance(**n, c))
+            .collect::<Vec<_>>();
+        assert_eq!(found, scan[..5].to_vec());
+    }
+}
@@ -8,7 +8,7 @@ fn city() {
 
     for y in 0..city_size / 2 {
         for x in 0..city_size * 2 {
-            let mut node = node!(spread * x as i16, spread * y as i16);
+            let mut node = node!(spread * x as i32, spread * y as i32);
             node.color = tools::seed_rgba((city_size * x + spread * y) as u64);
             pos.push(node);
         }
@@ -22,6 +22,27 @@ fn city() {
     Map::new().map_filter(&pos, &|node: &Node| node.hl(0).is_ok());
 }
 
+#[test]
+fn geo() {
+    let viewport = geo::Viewport::new(
+        geo::Projection::Mercator,
+        geo::LatLon::new(55.0, 10.0),
+        geo::LatLon::new(69.0, 25.0),
+        200,
+    );
+    let nodes = Node::linked_list(vec![
+        viewport.node("A", geo::LatLon::new(59.3293, 18.0686)),
+        viewport.node("B", geo::LatLon::new(57.7089, 11.9746)),
+        viewport.node("C", geo::LatLon::new(55.6050, 13.0038)),
+    ]);
+    let path = Network::new(nodes.clone())
+        .path_weighted("A", "C", &|a, b| viewport.distance(a, b))
+        .unwrap();
+
+    assert_eq!(path.len(), 3);
+    Map::new_geo(&viewport).map(&nodes);
+}
+
 #[test]
 fn git_log() {}
 
@@ -76,16 +97,17 @@ fn ellipse() {
 }
 
 #[test]
-fn cycles() -> std::io::Result<()> {
+fn timeline() -> std::io::Result<()> {
+    use map::timeline::*;
     let mut gif = map::gif::Gif::new("/tmp/out.gif", 50, 50);
-    let balls = Node::from_list(&[(20, 20), (30, 30), (40, 40)]);
-
-    gif.cycle(2, balls);
-
-    for _ in 0..4 {
-        gif.blank()?;
+    let mut timeline = Timeline::new();
+    for ball in Node::from_list(&[(20, 20), (30, 30), (40, 40)]) {
+        let to = ball.geo + coordinate!(0, 5);
+        timeline.sequential(Track::new(ball).key(Keyframe::new(0.2).geo(to)));
     }
 
+    timeline.render(&mut gif, 10)?;
+    assert_eq!(gif.frames(), 7);
     Ok(())
 }
 
@@ -96,9 +118,9 @@ fn graph() {
 
     let mut pos = Vec::new();
     let y = vec![5, 30, 45, 35, 40, 80, 75, 70, 25, 30];
-    let spread = 300 / (y.len() - 1) as i16;
+    let spread = 300 / (y.len() - 1) as i32;
     for (i, y) in y.iter().enumerate() {
-        pos.push((i as i16 * spread, -*y));
+        pos.push((i as i32 * spread, -*y));
     }
     let line = Node::linked_list(Node::from_list(&pos));
 
@@ -1,7 +1,7 @@
 use crate::*;
 
 // List used for calling from_list tests.
-fn get_list<'a>() -> &'a [(i16, i16); 8] {
+fn get_list<'a>() -> &'a [(i32, i32); 8] {
     &[
         (0, 0),         // Default test,
         (100, 100),     // Two positive values test,
@@ -203,4 +203,887 @@ mod integration {
             assert!(b.settings.is_directly_connected(&a));
         }
     }
+
+    mod arrow {
+        use crate::*;
+
+        /**
+        Maps an edge from (40, 0) to (0, 0), returning the image and where (0, 0) is on it.
+         */
+        fn map(arrow: Arrow) -> (IW, (u32, u32)) {
+            let b = Node::new("B", Coordinate::new(0, 0));
+            let mut a = Node::new("A", Coordinate::new(40, 0));
+            a.link(&b);
+            a.hl_mut(0).unwrap().arrow(arrow);
+            let map = Map::new().map(&[a]);
+            let offset = map.offset();
+            (map.consume(), (offset.x as u32, offset.y as u32))
+        }
+
+        fn dark(arrow: Arrow, x: i32, y: i32) -> bool {
+            let (image, (ox, oy)) = map(arrow);
+            let p = image
+                .image()
+                .get_pixel((ox as i32 + x) as u32, (oy as i32 + y) as u32);
+            p[3] == 255 && p[0] <= 40
+        }
+
+        #[test]
+        fn default_is_unchanged() {
+            let (plain, _) = map(Arrow::new());
+            let (image, _) = map(Arrow::new().clip(0));
+            assert_eq!(
+                plain.image().as_ref() as &[u8],
+                image.image().as_ref() as &[u8]
+            );
+        }
+
+        #[test]
+        fn clipped_edge_stops_at_node() {
+            assert!(dark(Arrow::new(), 2, 0));
+            assert!(!dark(Arrow::new().clip(8), 2, 0));
+            assert!(dark(Arrow::new().clip(8), 10, 0));
+        }
+
+        #[test]
+        fn clipped_to_shape_of_node() {
+            let mut b = Node::new("B", Coordinate::new(0, 0));
+            b.shape = Some(Shape::Diamond);
+            b.radius = Some(12);
+            assert!(!dark(Arrow::new().clip(12), 8, 0));
+            assert!(dark(Arrow::new().clip_to(&b), 8, 0));
+            b.shape = None;
+            assert!(!dark(Arrow::new().clip_to(&b), 8, 0));
+        }
+
+        #[test]
+        fn head_is_wider_than_edge() {
+            assert!(!dark(Arrow::new().clip(4), 6, 2));
+            assert!(dark(Arrow::new().clip(4).head(6), 6, 2));
+        }
+
+        #[test]
+        fn chevrons_along_edge() {
+            assert!(!dark(Arrow::new(), 25, 3));
+            assert!(dark(Arrow::new().chevrons(1), 25, 3));
+        }
+    }
+
+    mod parallel {
+        use crate::*;
+
+        /**
+        Returns a network larger than a tile, using every kind of edge, arrow, stroke and shape.
+         */
+        fn network() -> Vec<Node> {
+            let styles = [
+                EdgeStyle::Direct,
+                EdgeStyle::Straight,
+                EdgeStyle::Ellipse,
+                EdgeStyle::Quadratic(40),
+                EdgeStyle::Cubic(-30),
+                EdgeStyle::CatmullRom(coordinate!(20, -40)),
+                EdgeStyle::Orthogonal,
+                EdgeStyle::Bundled(80),
+            ];
+            let shapes = [
+                Shape::Circle,
+                Shape::Triangle,
+                Shape::Star,
+                Shape::Ring,
+                Shape::Custom(|size| vec![coordinate!(-(size as i32) * 3, 0)]),
+            ];
+            let list = (0..60)
+                .map(|i| ((i * 97) % 600, (i * 53) % 500))
+                .collect::<Vec<_>>();
+            let mut nodes = Node::linked_list(Node::from_list(&list));
+            for (i, node) in nodes.iter_mut().enumerate() {
+                node.color = tools::seed_rgba(i as u64);
+                node.radius = Some(3 + i as u32 % 9);
+                node.shape = Some(shapes[i % shapes.len()]);
+                node.border = if i % 4 == 0 { Some(1) } else { None };
+                node.z = (i % 3) as i32;
+                if i % 7 == 0 {
+                    node.sprite = Some(Sprite::new("icon").scale(1.5));
+                }
+                if let Ok(hl) = node.hl_mut(0) {
+                    hl.style(styles[i % styles.len()]);
+                    hl.arrow(Arrow::new().head(i as u32 % 8).chevrons(i as u32 % 2));
+                    hl.stroke(Stroke::Dashed(4, i as u32 % 3));
+                }
+            }
+            nodes
+        }
+
+        fn icon() -> image::RgbaImage {
+            image::RgbaImage::from_fn(9, 9, |x, y| image::Rgba([40, 200, 90, (x * y * 3) as u8]))
+        }
+
+        fn assert_identical(a: Map, b: Map) {
+            assert_eq!(a.hitboxes(), b.hitboxes());
+            let (a, b) = (a.consume(), b.consume());
+            assert_eq!(a.dimensions(), b.dimensions());
+            assert!(a.image().as_ref() as &[u8] == b.image().as_ref() as &[u8]);
+        }
+
+        #[test]
+        fn identical_to_sequential() {
+            let nodes = network();
+            assert_identical(
+                Map::new().sprite("icon", icon()).map(&nodes),
+                Map::new().sprite("icon", icon()).map_parallel(&nodes),
+            );
+        }
+
+        #[test]
+        fn identical_on_top_of_earlier_layers() {
+            let nodes = network();
+            let (first, second) = nodes.split_at(30);
+            let map = || {
+                Map::new()
+                    .sprite("icon", icon())
+                    .map_absolute(&nodes)
+                    .map_layer(Layer::Overlay, &first[..5])
+                    .map(first)
+            };
+            assert_identical(map().map(second), map().map_parallel(second));
+        }
+
+        #[test]
+        fn identical_for_groups() {
+            let mut groups = Vec::new();
+            for i in 0..4 {
+                let mut group = Group::new_simple(i * 150, i * 90);
+                group.set().z = 3 - i;
+                group.add(300);
+                groups.push(group);
+            }
+            assert_identical(Map::new().map(&groups), Map::new().map_parallel(&groups));
+        }
+
+        #[test]
+        fn tiles_share_rasterised_shapes() {
+            let map = Map::new().map_parallel(&network());
+            let areas = map.image.as_ref().unwrap().areas.lock().unwrap();
+            let sequential = Map::new().map(&network());
+            let expected = sequential.image.as_ref().unwrap().areas.lock().unwrap();
+            assert_eq!(areas.len(), expected.len());
+        }
+
+        #[test]
+        fn bounds_cover_drawn_pixels() {
+            let mut a = Node::new("A", Coordinate::new(0, 0));
+            let b = Node::new("B", Coordinate::new(100, 40));
+            a.link(&b);
+            a.hl_mut(0).unwrap().style(EdgeStyle::Quadratic(80));
+            let bounds = a.bounds(&Shape::Square).unwrap();
+            assert!(a.hl(0).unwrap().plot().iter().all(|c| bounds.contains(c)));
+
+            a.hl_mut(0).unwrap().style(EdgeStyle::Orthogonal);
+            assert_eq!(a.bounds(&Shape::Square), None);
+        }
+    }
+
+    mod layer {
+        use crate::*;
+
+        const RED: image::Rgba<u8> = image::Rgba {
+            data: [200, 0, 0, 255],
+        };
+
+        /**
+        Returns two overlapping Nodes, the second one red.
+         */
+        fn overlapping() -> (Node, Node) {
+            let a = Node::new("A", Coordinate::new(0, 0));
+            let mut b = Node::new("B", Coordinate::new(1, 1));
+            b.color = RED;
+            (a, b)
+        }
+
+        fn pixel(map: Map, c: Coordinate) -> image::Rgba<u8> {
+            let c = c + map.offset();
+            *map.consume().image().get_pixel(c.x as u32, c.y as u32)
+        }
+
+        #[test]
+        fn edges_beneath_nodes() {
+            let (_, b) = overlapping();
+            let mut c = Node::new("C", Coordinate::new(20, 20));
+            c.link(&b);
+            let map = Map::new().map(&[b, c]).map(&[c]);
+            assert_eq!(pixel(map, b.geo), RED);
+        }
+
+        #[test]
+        fn z_index_orders_nodes() {
+            let (mut a, b) = overlapping();
+            assert_eq!(pixel(Map::new().map(&[a, b]), Coordinate::new(2, 2)), RED);
+            a.z = 1;
+            assert_eq!(
+                pixel(Map::new().map(&[a, b]), Coordinate::new(2, 2)),
+                a.color
+            );
+        }
+
+        #[test]
+        fn group_z_index_is_added() {
+            let (a, b) = overlapping();
+            let mut first = Group::new("A", Coordinate::new(0, 0));
+            first.push(a);
+            let mut second = Group::new("B", Coordinate::new(0, 0));
+            second.push(b);
+            let groups = [first.clone(), second.clone()];
+            assert_eq!(pixel(Map::new().map(&groups), Coordinate::new(2, 2)), RED);
+            first.set().z = 1;
+            let groups = [first, second];
+            assert_eq!(
+                pixel(Map::new().map(&groups), Coordinate::new(2, 2)),
+                a.color
+            );
+        }
+
+        #[test]
+        fn z_index_across_map_calls() {
+            let (mut a, b) = overlapping();
+            a.z = 1;
+            let map = Map::new().map(&[a]).map(&[b]);
+            assert_eq!(pixel(map, Coordinate::new(2, 2)), a.color);
+        }
+
+        #[test]
+        fn semi_transparent_blends_with_lower_z_index() {
+            let (a, mut b) = overlapping();
+            b.color = image::Rgba([200, 0, 0, 100]);
+            b.z = 5;
+            let map = Map::new().map(&[b]).map(&[a]);
+            let mut expected = a.color;
+            image::Pixel::blend(&mut expected, &b.color);
+            assert_eq!(pixel(map, Coordinate::new(2, 2)), expected);
+        }
+
+        #[test]
+        fn semi_transparent_replaces_same_z_index() {
+            let (a, mut b) = overlapping();
+            b.color = image::Rgba([200, 0, 0, 100]);
+            let map = Map::new().map(&[a, b]);
+            assert_eq!(pixel(map, Coordinate::new(2, 2)), b.color);
+        }
+
+        #[test]
+        fn only_other_z_indexes_are_recorded() {
+            let mut group = Group::new_simple(0, 0);
+            group.add(500);
+            group.nodes = Node::linked_list(group.nodes);
+            let (mut a, mut b) = overlapping();
+            a.z = 1;
+            b.z = -1;
+
+            let map = Map::new().map(&[group]).map(&[a, b]);
+            let image = map.image.as_ref().unwrap();
+            assert_eq!(image.layers.len(), 2);
+            let calls = image
+                .layers
+                .values()
+                .map(|level| level.calls.len())
+                .sum::<usize>();
+            assert_eq!(calls, 2);
+
+            // A copy holds one image per layer, rather than everything drawn so far.
+            let copy = map.clone().map(&[a]);
+            assert_eq!(copy.image.as_ref().unwrap().layers.len(), 2);
+        }
+
+        #[test]
+        fn overlay_above_later_nodes() {
+            let (a, b) = overlapping();
+            let map = Map::new()
+                .map(&[a, b])
+                .map_layer(Layer::Overlay, &[b])
+                .map(&[a]);
+            assert_eq!(pixel(map, Coordinate::new(2, 2)), RED);
+        }
+
+        #[test]
+        fn density_beneath_nodes() {
+            let (a, _) = overlapping();
+            let heatmap = map::heatmap::Heatmap::new().color(RED);
+            let map = Map::new().map(&[a]).map_density(&[a], &heatmap);
+            assert_eq!(pixel(map, Coordinate::new(2, 2)), a.color);
+        }
+
+        #[test]
+        fn density_blends_with_background() {
+            let blue = image::Rgba([0, 0, 255, 255]);
+            let photo = image::RgbaImage::from_pixel(20, 20, blue);
+            let heatmap = map::heatmap::Heatmap::new()
+                .cell(4)
+                .color(image::Rgba([255, 0, 0, 128]));
+            let nodes = Node::from_list(&[(5, 5)]);
+            let image = Map::new_background(photo)
+                .map_density(&nodes, &heatmap)
+                .consume();
+            assert!(image.image().pixels().all(|p| p[2] > 0));
+            assert!(image.image().pixels().any(|p| p[0] > 0));
+        }
+
+        #[test]
+        fn consumed_image_is_drawn_on_directly() {
+            let (a, b) = overlapping();
+            let map = Map::new().map(&[a]);
+            let offset = map.offset();
+            let mut image = map.consume();
+            b.draw(&mut image, offset, &Shape::Square);
+            let c = Coordinate::new(2, 2) + offset;
+            assert_eq!(image.image().get_pixel(c.x as u32, c.y as u32), &RED);
+        }
+    }
+
+    mod sprite {
+        use crate::*;
+
+        const BLUE: image::Rgba<u8> = image::Rgba {
+            data: [20, 120, 200, 255],
+        };
+
+        fn background() -> Map {
+            Map::new_background(image::RgbaImage::new(40, 40))
+        }
+
+        fn node(sprite: Sprite) -> Node {
+            let mut node = Node::new("A", Coordinate::new(20, 20));
+            node.sprite = Some(sprite);
+            node
+        }
+
+        fn covered(image: &IW) -> Vec<(u32, u32)> {
+            image
+                .image()
+                .enumerate_pixels()
+                .filter(|(_, _, &p)| p == BLUE)
+                .map(|(x, y, _)| (x, y))
+                .collect()
+        }
+
+        #[test]
+        fn sprite_is_centered_and_scaled() {
+            let icon = image::RgbaImage::from_pixel(8, 8, BLUE);
+            let image = background()
+                .sprite("icon", icon.clone())
+                .map(&[node(Sprite::new("icon"))])
+                .consume();
+            let covered = covered(&image);
+            assert_eq!(covered.len(), 64);
+            assert_eq!(covered[0], (15, 15));
+
+            let image = background()
+                .sprite("icon", icon)
+                .map(&[node(Sprite::new("icon").scale(0.5))])
+                .consume();
+            assert_eq!(self::covered(&image).len(), 16);
+        }
+
+        #[test]
+        fn transparent_pixels_are_blended() {
+            let icon = image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 0, 0]));
+            let image = Map::new_background(image::RgbaImage::from_pixel(40, 40, BLUE))
+                .sprite("icon", icon)
+                .map(&[node(Sprite::new("icon"))])
+                .consume();
+            assert_eq!(covered(&image).len(), 40 * 40);
+        }
+
+        #[test]
+        fn missing_sprite_draws_shape() {
+            let image = background().map(&[node(Sprite::new("icon"))]).consume();
+            assert_eq!(image.image().get_pixel(20, 20)[3], 255);
+        }
+
+        #[test]
+        fn background_keeps_positions() {
+            let image = background()
+                .map(&Node::from_list(&[(0, 0), (30, 30)]))
+                .consume();
+            assert_eq!(image.dimensions(), Coordinate::new(40, 40));
+            assert_eq!(image.image().get_pixel(31, 31)[3], 255);
+            assert_eq!(image.image().get_pixel(20, 20)[3], 0);
+        }
+
+        #[test]
+        fn open_missing_background() {
+            let res = Map::open_background(std::path::Path::new("/tmp/does/not/exist.png"));
+            assert!(res.is_err());
+        }
+    }
+
+    mod shape {
+        use crate::*;
+
+        /**
+        Maps a single Node and returns the image and where the Node is on it.
+         */
+        fn map(node: Node) -> (IW, Coordinate) {
+            let map = Map::new().map(&[node]);
+            let offset = map.offset();
+            (map.consume(), node.geo + offset)
+        }
+
+        fn is_drawn(image: &IW, c: Coordinate) -> bool {
+            image.image().get_pixel(c.x as u32, c.y as u32)[3] == 255
+        }
+
+        #[test]
+        fn node_shape_overrides_map_shape() {
+            let mut node = Node::new("A", Coordinate::new(0, 0));
+            node.radius = Some(8);
+            let (square, at) = map(node);
+            node.shape = Some(Shape::Diamond);
+            let (diamond, _) = map(node);
+            let corner = at - coordinate!(1, 1);
+            assert!(is_drawn(&square, corner));
+            assert!(!is_drawn(&diamond, corner));
+            assert!(is_drawn(&diamond, corner + coordinate!(4, 4)));
+        }
+
+        #[test]
+        fn border_leaves_inside_hollow() {
+            let mut node = Node::new("A", Coordinate::new(0, 0));
+            node.radius = Some(8);
+            node.border = Some(2);
+            let (image, at) = map(node);
+            let corner = at - coordinate!(1, 1);
+            assert!(is_drawn(&image, corner + coordinate!(1, 4)));
+            assert!(!is_drawn(&image, corner + coordinate!(4, 4)));
+        }
+
+        #[test]
+        fn custom_shape() {
+            let mut node = Node::new("A", Coordinate::new(0, 0));
+            node.shape = Some(Shape::Custom(|_| vec![Coordinate::new(2, 0)]));
+            let (image, at) = map(node);
+            let corner = at - coordinate!(1, 1);
+            assert!(is_drawn(&image, corner + coordinate!(2, 0)));
+            assert!(!is_drawn(&image, corner));
+        }
+
+        #[test]
+        fn shapes_fit_their_size() {
+            let shapes = [
+                Shape::Square,
+                Shape::Diamond,
+                Shape::Hexagon,
+                Shape::Star,
+                Shape::RoundedRect,
+            ];
+            for shape in shapes.iter() {
+                let area = shape.area(10);
+                assert!(!area.is_empty());
+                assert!(area
+                    .iter()
+                    .all(|c| c.x >= 0 && c.y >= 0 && c.x < 10 && c.y < 10));
+            }
+        }
+
+        #[test]
+        fn areas_are_unique() {
+            for shape in [Shape::Circle, Shape::Triangle, Shape::Ring].iter() {
+                for size in 1..20 {
+                    let mut area = shape.area(size);
+                    let len = area.len();
+                    area.sort_by_key(|c| (c.x, c.y));
+                    area.dedup();
+                    assert_eq!(area.len(), len);
+                }
+            }
+        }
+
+        #[test]
+        fn circle_is_symmetric() {
+            let area = Shape::Circle.area(7);
+            assert!(area.contains(&Coordinate::new(0, 6)));
+            assert!(area.contains(&Coordinate::new(0, -6)));
+            for c in area.iter() {
+                assert!(area.contains(&Coordinate::new(-c.x, -c.y)));
+                assert!(area.contains(&Coordinate::new(c.y, c.x)));
+            }
+            assert_eq!(Shape::Circle.area(1), vec![Coordinate::new(0, 0)]);
+        }
+
+        #[test]
+        fn areas_are_cached() {
+            let mut image = IW::from(map::gen_canvas(1, 1));
+            let a = image.area(&Shape::Star, 9, None);
+            let b = image.area(&Shape::Star, 9, None);
+            assert!(std::sync::Arc::ptr_eq(&a, &b));
+            assert!(!std::sync::Arc::ptr_eq(
+                &a,
+                &image.area(&Shape::Star, 9, Some(1))
+            ));
+            assert!(!std::sync::Arc::ptr_eq(
+                &a,
+                &image.area(&Shape::Star, 8, None)
+            ));
+            assert_eq!(image.area(&Shape::Custom(|_| Vec::new()), 9, None).len(), 0);
+        }
+
+        #[test]
+        fn ring_is_hollow_circle() {
+            let ring = Shape::Ring.area(9);
+            let circle = Shape::Circle.area(9);
+            assert!(!ring.contains(&Coordinate::new(0, 0)));
+            assert!(circle.contains(&Coordinate::new(0, 0)));
+            assert!(ring.iter().all(|c| circle.contains(c)));
+        }
+    }
+
+    mod stroke {
+        use crate::*;
+
+        const RED: image::Rgba<u8> = image::Rgba {
+            data: [200, 0, 0, 255],
+        };
+
+        fn nodes() -> Vec<Node> {
+            let b = Node::new("B", Coordinate::new(0, 0));
+            let mut a = Node::new("A", Coordinate::new(40, 0));
+            a.link(&b);
+            vec![a, b]
+        }
+
+        /**
+        Returns the colors of the edge pixels between the Nodes.
+         */
+        fn edge(map: Map) -> Vec<image::Rgba<u8>> {
+            let offset = map.offset();
+            let image = map.consume();
+            (10..30)
+                .map(|x| {
+                    *image
+                        .image()
+                        .get_pixel((offset.x + x) as u32, offset.y as u32)
+                })
+                .collect()
+        }
+
+        fn styled(stroke: Stroke) -> Vec<image::Rgba<u8>> {
+            let mut nodes = nodes();
+            nodes[0].hl_mut(0).unwrap().stroke(stroke);
+            edge(Map::new().map(&nodes))
+        }
+
+        #[test]
+        fn solid_is_unbroken() {
+            assert!(styled(Stroke::Solid).iter().all(|p| p[3] == 255));
+        }
+
+        /**
+        Returns how many of the edge pixels are drawn, each step covers two pixels since the edge is two pixels wide.
+         */
+        fn drawn(stroke: Stroke) -> usize {
+            styled(stroke).iter().filter(|p| p[3] == 255).count()
+        }
+
+        #[test]
+        fn dashed_leaves_gaps() {
+            assert_eq!(drawn(Stroke::Dashed(3, 2)), 16);
+            assert_eq!(drawn(Stroke::Dashed(1, 0)), 20);
+        }
+
+        #[test]
+        fn dotted_leaves_gaps() {
+            assert_eq!(drawn(Stroke::Dotted(4)), 10);
+            assert_eq!(drawn(Stroke::Pattern(0)), 0);
+        }
+
+        #[test]
+        fn color_replaces_shade() {
+            let mut nodes = nodes();
+            nodes[0].hl_mut(0).unwrap().color(RED);
+            assert!(edge(Map::new().map(&nodes)).iter().all(|&p| p == RED));
+        }
+
+        #[test]
+        fn map_styled_restyles_edges() {
+            let image = edge(Map::new().map_styled(&nodes(), &|hl| {
+                let mut hl = *hl;
+                hl.color(RED);
+                hl
+            }));
+            assert!(image.iter().all(|&p| p == RED));
+        }
+    }
+
+    mod edge_style {
+        use crate::*;
+
+        /**
+        Returns how many edge pixels cross the column of a Node placed between the ends of the edge,
+        outside of the Node itself.
+         */
+        fn crossings(style: EdgeStyle, others: &[Node]) -> usize {
+            let b = Node::new("B", Coordinate::new(0, 0));
+            let c = Node::new("C", Coordinate::new(40, 0));
+            let mut a = Node::new("A", Coordinate::new(80, 0));
+            a.link(&b);
+            a.hl_mut(0).unwrap().style(style);
+            // Makes room above and below the edge for it to go around.
+            let mut nodes = Node::from_list(&[(0, -20), (0, 20)]);
+            nodes.extend_from_slice(&[a, b, c]);
+            nodes.extend_from_slice(others);
+            let map = Map::new().map(&nodes);
+            let offset = map.offset();
+            let image = map.consume();
+            let image = image.image();
+            let x = (offset.x + 40) as u32;
+            (0..image.height())
+                .filter(|&y| (y as i32 - offset.y).abs() > c.size() as i32)
+                .filter(|&y| image.get_pixel(x, y)[3] == 255)
+                .count()
+        }
+
+        #[test]
+        fn direct_goes_through_node() {
+            assert_eq!(crossings(EdgeStyle::Direct, &[]), 0);
+        }
+
+        #[test]
+        fn orthogonal_goes_around_node() {
+            assert!(crossings(EdgeStyle::Orthogonal, &[]) > 0);
+        }
+
+        #[test]
+        fn orthogonal_goes_around_node_among_distant_nodes() {
+            let others = (0..40)
+                .map(|i| Node::new(&format!("far {}", i), Coordinate::new(600 + i * 10, 600)))
+                .collect::<Vec<_>>();
+            assert!(crossings(EdgeStyle::Orthogonal, &others) > 0);
+        }
+    }
+
+    mod hitbox {
+        use crate::*;
+
+        #[test]
+        fn nodes_in_groups() {
+            let mut group = Group::new_simple(0, 0);
+            group.add(20);
+            let map = Map::new().map(&[group.clone()]);
+            assert_eq!(map.hitboxes().len(), group.nodes.len());
+            for node in group.nodes.iter().rev() {
+                let at = node.geo + group.position() + map.offset();
+                let hit = map.element_at(at.x as u32, at.y as u32);
+                assert!(hit.is_some());
+            }
+        }
+
+        #[test]
+        fn last_drawn_on_top() {
+            let a = Node::new("A", coordinate!(0, 0));
+            let b = Node::new("B", coordinate!(1, 1));
+            let map = Map::new().map(&[a, b]);
+            let at = a.geo + map.offset();
+            assert_eq!(map.element_at(at.x as u32, at.y as u32), Some(b.hash()));
+            let map = Map::new().map(&[b, a]);
+            assert_eq!(map.element_at(at.x as u32, at.y as u32), Some(a.hash()));
+        }
+
+        #[test]
+        fn ordered_by_layer_and_z_index() {
+            let mut a = Node::new("A", coordinate!(0, 0));
+            let b = Node::new("B", coordinate!(1, 1));
+            let c = Node::new("C", coordinate!(2, 2));
+            a.z = 1;
+            let map = Map::new()
+                .map_layer(Layer::Overlay, &[c])
+                .map(&[a])
+                .map(&[b]);
+
+            let at = b.geo + map.offset();
+            assert_eq!(map.element_at(at.x as u32, at.y as u32), Some(c.hash()));
+            let order = map.hitboxes().iter().map(|h| h.hash).collect::<Vec<_>>();
+            assert_eq!(order, vec![b.hash(), a.hash(), c.hash()]);
+
+            let html = map::hitbox::html(map.hitboxes(), "z");
+            let position = |n: &Node| html.find(&format!("#{}\"", n.hash())).unwrap();
+            assert!(position(&c) < position(&a) && position(&a) < position(&b));
+
+            let map = Map::new().map(&[a]).map(&[b]);
+            let at = a.geo + map.offset();
+            assert_eq!(map.element_at(at.x as u32, at.y as u32), Some(a.hash()));
+        }
+
+        #[test]
+        fn covers_drawn_pixels() {
+            let mut nodes = Node::from_list(&[(0, 0), (30, 10)]);
+            nodes[1].shape = Some(Shape::Star);
+            nodes[1].radius = Some(9);
+            let map = Map::new().map(&nodes);
+            let hitboxes = map.hitboxes().to_vec();
+            let image = map.consume();
+            let mut drawn = Vec::new();
+            for (x, y, p) in image.image().enumerate_pixels() {
+                if p[3] > 0 {
+                    drawn.push(coordinate!(x, y));
+                }
+            }
+            for hitbox in &hitboxes {
+                let inside = drawn.iter().filter(|&&c| hitbox.contains(c));
+                let rect = inside.fold(None, |r: Option<Rect>, &c| {
+                    Some(r.map_or(Rect::new(c, c), |r| r.union(Rect::new(c, c))))
+                });
+                assert_eq!(rect, Some(hitbox.rect));
+            }
+            assert!(drawn
+                .iter()
+                .all(|&c| hitboxes.iter().any(|h| h.contains(c))));
+        }
+
+        #[test]
+        fn sprite_area() {
+            let icon = image::RgbaImage::from_pixel(6, 4, image::Rgba([0, 0, 0, 255]));
+            let mut node = Node::new("A", coordinate!(10, 10));
+            node.sprite = Some(Sprite::new("icon"));
+            let map = Map::new_canvas(20, 20).sprite("icon", icon).map(&[node]);
+            let rect = map.hitboxes()[0].rect;
+            assert_eq!((rect.width(), rect.height()), (5, 3));
+        }
+
+        #[test]
+        fn outside_of_canvas() {
+            let node = Node::new("A", coordinate!(100, 100));
+            let map = Map::new_canvas(20, 20).map(&[node]);
+            assert!(map.hitboxes().is_empty());
+        }
+
+        #[test]
+        fn saved_files() -> std::io::Result<()> {
+            let node = Node::new("A", coordinate!(0, 0));
+            let map = Map::new().map(&[node]);
+            let (html, json) = (
+                std::env::temp_dir().join("pathtracer_hitboxes.html"),
+                std::env::temp_dir().join("pathtracer_hitboxes.json"),
+            );
+            map.save_image_map(&html, "network")?;
+            map.save_hitboxes(&json)?;
+            assert_eq!(
+                std::fs::read_to_string(&html)?,
+                map::hitbox::html(map.hitboxes(), "network")
+            );
+            assert_eq!(
+                std::fs::read_to_string(&json)?,
+                map::hitbox::json(map.hitboxes())
+            );
+            Ok(())
+        }
+    }
+
+    mod map {
+        use crate::*;
+
+        #[test]
+        fn write_to_without_image() {
+            let mut bytes = Vec::new();
+            let res = Map::new().write_to(&mut bytes, image::ImageOutputFormat::PNG);
+            assert!(res.is_err());
+            assert!(bytes.is_empty());
+        }
+
+        #[test]
+        fn write_to_matches_dimensions() -> std::io::Result<()> {
+            let map = Map::new().map(&Node::from_list(&[(0, 0), (40, 20)]));
+            let dim = map.clone().consume().dimensions();
+            let mut bytes = Vec::new();
+            map.write_to(&mut bytes, image::ImageOutputFormat::PNG)?;
+            let image = image::load_from_memory(&bytes).unwrap().to_rgba();
+            assert_eq!(coordinate!(image.width(), image.height()), dim);
+            Ok(())
+        }
+
+        #[test]
+        fn save_without_image() {
+            let nodes: Vec<Node> = Vec::new();
+            let err = Map::new()
+                .map(&nodes)
+                .map_parallel(&nodes)
+                .save(std::path::Path::new("/tmp/pathtracer_unmapped.png"))
+                .unwrap_err();
+            let reason = err.get_ref().and_then(|e| e.downcast_ref::<MapError>());
+            assert_eq!(reason, Some(&MapError::Empty));
+        }
+
+        #[test]
+        fn empty_elements_on_canvas() {
+            let nodes: Vec<Node> = Vec::new();
+            let image = Map::new_canvas(20, 10).map(&nodes).try_consume();
+            assert_eq!(image.map(|x| x.dimensions()), Ok(coordinate!(20, 10)));
+        }
+
+        #[test]
+        fn background_beneath_everything() {
+            let white = image::Rgba([255, 255, 255, 255]);
+            let node = Node::new("A", coordinate!(10, 10));
+            let drawn = Map::new_canvas(20, 20).map(&[node]).consume();
+            let image = Map::new_canvas(20, 20)
+                .map(&[node])
+                .background(white)
+                .consume();
+            for (x, y, p) in image.image().enumerate_pixels() {
+                let q = drawn.image().get_pixel(x, y);
+                assert_eq!(p, if q[3] == 0 { &white } else { q });
+            }
+        }
+
+        #[test]
+        fn background_beneath_lowest_layer() {
+            let white = image::Rgba([255, 255, 255, 255]);
+            let mut node = Node::new("A", coordinate!(10, 10));
+            node.color = image::Rgba([0, 0, 200, 128]);
+            node.z = i32::MIN;
+            let image = Map::new_canvas(20, 20)
+                .background(white)
+                .map_layer(Layer::Background, &[node])
+                .consume();
+
+            let mut expected = white;
+            image::Pixel::blend(&mut expected, &node.color);
+            assert_eq!(image.image().get_pixel(11, 11), &expected);
+            assert_eq!(image.image().get_pixel(0, 0), &white);
+        }
+
+        #[test]
+        fn consume_without_image() {
+            let image = Map::new().consume();
+            assert_eq!(image.dimensions(), coordinate!());
+        }
+
+        #[test]
+        fn legend_and_density_before_mapping() {
+            let legend = color::legend::Legend::new();
+            let heatmap = map::heatmap::Heatmap::new();
+            let nodes = Node::from_list(&[(0, 0), (40, 20)]);
+            let image = Map::new()
+                .legend(&legend)
+                .map_density(&nodes, &heatmap)
+                .consume();
+            assert_eq!(
+                image.dimensions(),
+                Map::new().map(&nodes).consume().dimensions()
+            );
+        }
+
+        #[test]
+        fn drawn_on_borrowed_image() {
+            let nodes = Node::linked_list(Node::from_list(&[(0, 0), (40, 20)]));
+            let map = Map::new().map(&nodes);
+            let offset = map.offset();
+            let mapped = map.consume();
+            let dim = mapped.dimensions();
+            let mut image = IW::layered(map::gen_canvas(dim.x as u32, dim.y as u32));
+            for node in &nodes {
+                node.draw(&mut image, offset, &Shape::Square);
+            }
+            image.flatten(None);
+            assert!(image.image().as_ref() as &[u8] == mapped.image().as_ref() as &[u8]);
+        }
+    }
 }
@@ -5,12 +5,13 @@ Utility functions
 extern crate image;
 extern crate rand;
 
-use super::{Coordinate, Hash};
+use super::{consts, Coordinate, Hash, Location, Rect};
 use image::Rgba;
 use rand::{distributions::Uniform, Rng};
 
 use std::{
     cmp::{max, min},
+    collections::HashMap,
     f64,
     mem::swap,
 };
@@ -56,7 +57,7 @@ assert_eq!(
 ```
 */
 pub fn range_color(
-    falloff: i16,
+    falloff: i32,
     base: image::Rgba<u8>,
     base_geo: Coordinate,
     to_geo: Coordinate,
@@ -187,12 +188,12 @@ pub fn plot(a: Coordinate, b: Coordinate) -> Vec<Coordinate> {
 pub fn plot_type(
     mut a: Coordinate,
     mut b: Coordinate,
-    plot_kind: &Fn(Coordinate, Coordinate) -> Vec<Coordinate>,
+    plot_kind: &dyn Fn(Coordinate, Coordinate) -> Vec<Coordinate>,
 ) -> Vec<Coordinate> {
     // If any of the coordinates are negative, interally add to make them positive.
     if a.lt(0) || b.lt(0) {
         let add = Coordinate::new(max(-a.x, -b.x), max(-a.y, -b.y));
-        plot_type(a + add, b + add, &plot_bresenham)
+        plot_type(a + add, b + add, plot_kind)
             .iter()
             .fold(vec![], |mut acc, c| {
                 acc.push(*c - add);
@@ -237,12 +238,12 @@ pub fn plot_bresenham(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
 
     for x in min(from.x, to.x)..=max(from.x, to.x) {
         for y in min(last_y, from.y)..=max(last_y, from.y) {
-            plot.push(Coordinate::new(x as i16, y));
+            plot.push(Coordinate::new(x, y));
         }
         last_y = from.y;
         error += delta_err;
         while error >= 0.50 {
-            from.y += f64::signum(delta_y) as i16;
+            from.y += f64::signum(delta_y) as i32;
             error -= 1.00;
         }
     }
@@ -277,6 +278,68 @@ pub fn plot_rectangle(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
     plot
 }
 
+/**
+Plots an arrowhead with its tip at a point, pointing away from the point behind it.
+
+The arrowhead is as long as the size, and as wide as the size at its base.
+Returns no Coordinates if the points are the same.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let tip = Coordinate::new(10, 0);
+let head = tools::plot_arrowhead(tip, Coordinate::new(0, 0), 4, true);
+assert!(head.contains(&tip));
+assert!(head.iter().all(|c| c.x <= 10 && c.x >= 6));
+```
+*/
+pub fn plot_arrowhead(
+    tip: Coordinate,
+    back: Coordinate,
+    size: u32,
+    filled: bool,
+) -> Vec<Coordinate> {
+    let (dx, dy) = (tip - back).normalize();
+    if dx == 0.0 && dy == 0.0 {
+        return Vec::new();
+    }
+
+    let size = f64::from(size);
+    // Rounded relative to the tip, so that the arrowhead is the same wherever it is drawn.
+    let round = |v: f64| (v + 0.5).floor() as i32;
+    let point = |along: f64, across: f64| {
+        tip + Coordinate::new(
+            round(-dx * along - dy * across),
+            round(-dy * along + dx * across),
+        )
+    };
+    let left = point(size, size / 2.0);
+    let right = point(size, -size / 2.0);
+
+    let mut plot = vec![tip];
+    if filled {
+        for c in line(left, right) {
+            plot.append(&mut line(tip, c));
+        }
+    } else {
+        plot.append(&mut line(tip, left));
+        plot.append(&mut line(tip, right));
+    }
+    plot
+}
+
+/**
+Plots a line including both of its ends.
+*/
+fn line(a: Coordinate, b: Coordinate) -> Vec<Coordinate> {
+    let mut plot = self::plot(a, b);
+    plot.push(a);
+    plot.push(b);
+    plot
+}
+
 /**
 Gives the midpoint between two points.
 
@@ -300,54 +363,431 @@ pub fn midpoint(a: Coordinate, b: Coordinate) -> Coordinate {
 /**
 Draws a line between two coordinate points in the form on a ellipse.
 
+The line is a quarter of an ellipse, leaving the first point horizontally and arriving at the second point vertically.
+It is sampled along its length, so that long edges have no gaps.
+
 Derived from: https://en.wikipedia.org/wiki/Ellipse
 
 
-## Experimental
+## Examples
 
-This functionality is heavily a work in progress and it's behaviour is unreliable.
+```
+# use pathtracer::*;
+let (a, b) = (Coordinate::new(0, 0), Coordinate::new(400, 300));
+let plot = tools::plot_ellipse(a, b);
+assert_eq!(plot.first(), Some(&a));
+assert_eq!(plot.last(), Some(&b));
+assert!(plot.windows(2).all(|w| (w[1] - w[0]).abs().sum() <= 2));
+```
 */
-pub fn plot_ellipse(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
-    let min = Coordinate::new(min(from.x, to.x), min(from.y, to.y));
-    let _max = Coordinate::new(max(from.x, to.x), max(from.y, to.y));
-    let _t_max = midpoint(from, to);
-
-    let mut result = Vec::new();
-    let t = f64::consts::PI / 2.0; // How the ellipse is angled. and how steep it angles.
-    let mut theta: f64 = 0.5; // starting angle.
-    let diff = from - to;
-    let step: f64 = t / 4.5; // Number of ellipse steps from start to finish.
-    let c = min;
-    let r: f64 = f64::from(diff.x / 2).abs(); // distance from center to node aka radius.
-
-    debug!(
-        "r: {} |t: {} |s: {} |from: {} |to: {} |diff: {} |c: {}",
-        r, t, step, from, to, diff, c
-    );
+pub fn plot_ellipse(from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+    // The ellipse is centered at the corner the line bends around, the points are at the ends of its axes.
+    let corner = coordinate!(from.x, to.y);
+    let samples = sample(&[from, corner, to], &|p, t| {
+        let theta = t * f64::consts::FRAC_PI_2;
+        Point(
+            p[1].0 + (p[2].0 - p[1].0) * theta.sin(),
+            p[1].1 + (p[0].1 - p[1].1) * theta.cos(),
+        )
+    });
+    plot_polyline(&samples)
+}
 
-    // -r = J shape. r = n shape.
-    let mut s = Coordinate::new(1, 1);
-    if from.y > to.y {
-        s.y = -1;
+/**
+Plots connected lines through every point, including the first and last point.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let points = Coordinate::from_list(&[(0, 0), (5, 0), (5, 5)]);
+let plot = tools::plot_polyline(&points);
+assert_eq!(plot.first(), Some(&Coordinate::new(0, 0)));
+assert_eq!(plot.last(), Some(&Coordinate::new(5, 5)));
+assert_eq!(plot.len(), 11);
+```
+*/
+pub fn plot_polyline(points: &[Coordinate]) -> Vec<Coordinate> {
+    let mut plot: Vec<Coordinate> = Vec::new();
+    for pair in points.windows(2) {
+        let mut line = self::plot(pair[0], pair[1]);
+        line.push(pair[0]);
+        line.push(pair[1]);
+        // Orders the line from the first to the second point.
+        line.sort_by_key(|&c| (c - pair[0]).dot(c - pair[0]));
+        for c in line {
+            if plot.last() != Some(&c) {
+                plot.push(c);
+            }
+        }
+    }
+    if plot.is_empty() {
+        plot.extend(points.first());
+    }
+    plot
+}
+
+/**
+Plots a quadratic Bézier curve from one point to another, pulled towards the control point.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let plot = tools::plot_quadratic(
+    Coordinate::new(0, 0),
+    Coordinate::new(10, 20),
+    Coordinate::new(20, 0),
+);
+assert!(plot.contains(&Coordinate::new(10, 10)));
+```
+*/
+pub fn plot_quadratic(from: Coordinate, control: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+    plot_polyline(&sample(&[from, control, to], &|p, t| {
+        let u = 1.0 - t;
+        u * u * p[0] + 2.0 * u * t * p[1] + t * t * p[2]
+    }))
+}
+
+/**
+Plots a cubic Bézier curve from one point to another, pulled towards the two control points.
+*/
+pub fn plot_cubic(
+    from: Coordinate,
+    a: Coordinate,
+    b: Coordinate,
+    to: Coordinate,
+) -> Vec<Coordinate> {
+    plot_polyline(&sample(&[from, a, b, to], &|p, t| {
+        let u = 1.0 - t;
+        u * u * u * p[0] + 3.0 * u * u * t * p[1] + 3.0 * u * t * t * p[2] + t * t * t * p[3]
+    }))
+}
+
+/**
+Plots a smooth Catmull-Rom spline passing through every point.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let points = Coordinate::from_list(&[(0, 0), (10, 10), (20, 0)]);
+let plot = tools::plot_catmull_rom(&points);
+assert!(points.iter().all(|c| plot.contains(c)));
+```
+*/
+pub fn plot_catmull_rom(points: &[Coordinate]) -> Vec<Coordinate> {
+    if points.len() < 3 {
+        return plot_polyline(points);
+    }
+
+    let mut samples = Vec::new();
+    for i in 0..points.len() - 1 {
+        let p = [
+            points[i.saturating_sub(1)],
+            points[i],
+            points[i + 1],
+            points[min(i + 2, points.len() - 1)],
+        ];
+        samples.append(&mut sample(&p, &|p, t| {
+            let (t2, t3) = (t * t, t * t * t);
+            0.5 * (2.0 * p[1]
+                + t * (p[2] - p[0])
+                + t2 * (2.0 * p[0] - 5.0 * p[1] + 4.0 * p[2] - p[3])
+                + t3 * (3.0 * p[1] - p[0] - 3.0 * p[2] + p[3]))
+        }));
     }
+    plot_polyline(&samples)
+}
+
+/**
+Plots a route made of horizontal and vertical lines, going around the obstacles.
+
+Obstacles containing either end of the route are ignored,
+as are obstacles further than consts::ROUTE_MARGIN outside of the area between the ends.
+Detours may still go around the obstacles considered, outside of that area.
+If no route is found, or if there are too many obstacles, the route is a simple L shape instead.
+
 
-    while t > theta {
-        let point = from + coordinate!(r * theta.cos(), f64::from(s.y) * r * theta.sin() / 2.0);
-        debug!("Theta: {} | Coordinate: {} <- {}", theta, point, from);
-        let mut line = plot_type(from, point, &plot_bresenham);
-        result.append(&mut line);
-        from = point;
-        theta += step;
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate, Rect};
+let wall = Rect::new(Coordinate::new(10, -5), Coordinate::new(12, 5));
+let plot = tools::plot_orthogonal(Coordinate::new(0, 0), Coordinate::new(20, 0), &[wall]);
+assert!(plot.iter().all(|c| !wall.contains(c)));
+assert_eq!(plot.last(), Some(&Coordinate::new(20, 0)));
+```
+*/
+pub fn plot_orthogonal(from: Coordinate, to: Coordinate, obstacles: &[Rect]) -> Vec<Coordinate> {
+    let area = Rect::new(from, to).expand(consts::ROUTE_MARGIN);
+    let blocking = obstacles
+        .iter()
+        .filter(|r| r.intersects(area) && !r.contains(&from) && !r.contains(&to))
+        .cloned()
+        .collect::<Vec<_>>();
+    if blocking.len() > MAX_OBSTACLES {
+        return plot_polyline(&[from, coordinate!(to.x, from.y), to]);
     }
-    result.append(&mut plot_type(from, to, &plot_bresenham));
-    debug!("{:#?}", result);
-    result
+    let corners =
+        route(from, to, &blocking).unwrap_or_else(|| vec![from, coordinate!(to.x, from.y), to]);
+    plot_polyline(&corners)
+}
+
+/**
+Plots an edge bundled with other edges going between the same areas.
+
+The plane is divided in to a hierarchy of squares, and the edge is pulled through the centers of the squares
+containing its ends, up to the smallest square containing both.
+Squares more than twice as large as the edge is long are skipped.
+Edges close to each other share these centers, which gathers them in to bundles.
+The strength is from 0, a straight line, to 100, following the centers as closely as possible.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let plot = tools::plot_bundled(Coordinate::new(0, 0), Coordinate::new(100, 40), 80);
+assert_eq!(plot.first(), Some(&Coordinate::new(0, 0)));
+assert_eq!(plot.last(), Some(&Coordinate::new(100, 40)));
+```
+*/
+pub fn plot_bundled(from: Coordinate, to: Coordinate, strength: u8) -> Vec<Coordinate> {
+    let center = |c: Coordinate, level: u32| {
+        let size = 1 << level;
+        coordinate!(
+            (c.x >> level) * size + size / 2,
+            (c.y >> level) * size + size / 2
+        )
+    };
+    let common = (BUNDLE_LEVEL..30)
+        .find(|&l| from.x >> l == to.x >> l && from.y >> l == to.y >> l)
+        .unwrap_or(30);
+    // Squares much larger than the edge would pull it far away from both of its ends.
+    let length = (to - from).length();
+    let limit = (BUNDLE_LEVEL..30)
+        .find(|&l| f64::from(1 << l) >= length / 2.0)
+        .unwrap_or(30);
+    let top = min(common, limit);
+
+    let mut points = vec![from];
+    points.extend((BUNDLE_LEVEL..top).map(|l| center(from, l)));
+    if common <= limit {
+        points.push(center(from, common));
+    }
+    points.extend((BUNDLE_LEVEL..top).rev().map(|l| center(to, l)));
+    points.push(to);
+
+    // Straightens the path towards the direct line between the ends.
+    let beta = f64::from(min(strength, 100)) / 100.0;
+    let last = (points.len() - 1) as f64;
+    let points = points
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| p.scale(beta) + from.lerp(to, i as f64 / last).scale(1.0 - beta))
+        .collect::<Vec<_>>();
+    plot_bspline(&points)
+}
+
+/**
+The smallest level of squares used when bundling edges, 2^4 = 16 pixels wide.
+*/
+const BUNDLE_LEVEL: u32 = 4;
+
+/**
+The number of obstacles near a route before orthogonal routing gives up searching for one.
+*/
+const MAX_OBSTACLES: usize = 32;
+
+/**
+The cost of turning, in pixels, when searching for an orthogonal route.
+*/
+const BEND_COST: i64 = 16;
+
+/**
+A point used when calculating curves.
+*/
+#[derive(Copy, Clone)]
+struct Point(f64, f64);
+
+impl std::ops::Add for Point {
+    type Output = Point;
+    fn add(self, o: Point) -> Point {
+        Point(self.0 + o.0, self.1 + o.1)
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Point;
+    fn sub(self, o: Point) -> Point {
+        Point(self.0 - o.0, self.1 - o.1)
+    }
+}
+
+impl std::ops::Mul<Point> for f64 {
+    type Output = Point;
+    fn mul(self, p: Point) -> Point {
+        Point(self * p.0, self * p.1)
+    }
+}
+
+/**
+Samples a curve defined by its control points, often enough for the samples to be at most a few pixels apart.
+*/
+fn sample(points: &[Coordinate], curve: &dyn Fn(&[Point], f64) -> Point) -> Vec<Coordinate> {
+    let p = points
+        .iter()
+        .map(|c| Point(f64::from(c.x), f64::from(c.y)))
+        .collect::<Vec<_>>();
+    let length: f64 = points.windows(2).map(|w| (w[1] - w[0]).length()).sum();
+    let steps = max((length / 2.0).ceil() as usize, 1);
+    (0..=steps)
+        .map(|i| {
+            let Point(x, y) = curve(&p, i as f64 / steps as f64);
+            coordinate!(x.round() as i32, y.round() as i32)
+        })
+        .collect()
+}
+
+/**
+Plots a uniform cubic B-spline, clamped so it starts and ends at the first and last point.
+*/
+fn plot_bspline(points: &[Coordinate]) -> Vec<Coordinate> {
+    if points.len() < 3 {
+        return plot_polyline(points);
+    }
+    let mut p = vec![points[0]; 2];
+    p.extend_from_slice(points);
+    p.push(points[points.len() - 1]);
+    p.push(points[points.len() - 1]);
+
+    let mut samples = Vec::new();
+    for w in p.windows(4) {
+        samples.append(&mut sample(w, &|p, t| {
+            let u = 1.0 - t;
+            (1.0 / 6.0)
+                * (u * u * u * p[0]
+                    + (3.0 * t * t * t - 6.0 * t * t + 4.0) * p[1]
+                    + (-3.0 * t * t * t + 3.0 * t * t + 3.0 * t + 1.0) * p[2]
+                    + t * t * t * p[3])
+        }));
+    }
+    plot_polyline(&samples)
+}
+
+/**
+Searches for the orthogonal route with the fewest turns and shortest length, returning its corners.
+
+Only lines along the sides of the obstacles, and through both ends, are searched.
+*/
+fn route(from: Coordinate, to: Coordinate, obstacles: &[Rect]) -> Option<Vec<Coordinate>> {
+    let mut xs = vec![from.x, to.x];
+    let mut ys = vec![from.y, to.y];
+    for r in obstacles {
+        xs.extend(&[r.min.x - 1, r.max.x + 1]);
+        ys.extend(&[r.min.y - 1, r.max.y + 1]);
+    }
+    for v in [&mut xs, &mut ys].iter_mut() {
+        v.sort();
+        v.dedup();
+    }
+    let at = |(x, y): (usize, usize)| coordinate!(xs[x], ys[y]);
+    let free = |a: Coordinate, b: Coordinate| {
+        let line = Rect::new(a, b);
+        !obstacles.iter().any(|r| r.intersects(line))
+    };
+    let start = (
+        xs.binary_search(&from.x).ok()?,
+        ys.binary_search(&from.y).ok()?,
+    );
+    let end = (xs.binary_search(&to.x).ok()?, ys.binary_search(&to.y).ok()?);
+
+    // Dijkstra over the grid, where the state is the position and the direction arrived from.
+    type State = ((usize, usize), usize);
+    let mut best: HashMap<State, i64> = HashMap::new();
+    let mut prev: HashMap<State, State> = HashMap::new();
+    let mut queue = std::collections::BinaryHeap::new();
+    for dir in 0..4 {
+        best.insert((start, dir), 0);
+        queue.push(std::cmp::Reverse((0, start, dir)));
+    }
+
+    while let Some(std::cmp::Reverse((cost, pos, dir))) = queue.pop() {
+        if pos == end {
+            let mut corners = vec![at(pos)];
+            let mut state = (pos, dir);
+            while let Some(&p) = prev.get(&state) {
+                if p.1 != state.1 {
+                    corners.push(at(p.0));
+                }
+                state = p;
+            }
+            corners.push(from);
+            corners.reverse();
+            corners.dedup();
+            return Some(corners);
+        }
+        if best.get(&(pos, dir)).is_some_and(|&c| c < cost) {
+            continue;
+        }
+
+        let (x, y) = pos;
+        let next = [
+            (x.checked_sub(1), Some(y)),
+            (Some(x + 1).filter(|&x| x < xs.len()), Some(y)),
+            (Some(x), y.checked_sub(1)),
+            (Some(x), Some(y + 1).filter(|&y| y < ys.len())),
+        ];
+        for (d, n) in next.iter().enumerate() {
+            let n = match n {
+                (Some(x), Some(y)) => (*x, *y),
+                _ => continue,
+            };
+            if !free(at(pos), at(n)) {
+                continue;
+            }
+            let turn = if d == dir || pos == start {
+                0
+            } else {
+                BEND_COST
+            };
+            let cost = cost + i64::from((at(n) - at(pos)).abs().sum()) + turn;
+            if best.get(&(n, d)).is_none_or(|&c| cost < c) {
+                best.insert((n, d), cost);
+                prev.insert((n, d), (pos, dir));
+                queue.push(std::cmp::Reverse((cost, n, d)));
+            }
+        }
+    }
+    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_arrowhead_same_points() {
+        let c = Coordinate::new(3, 3);
+        assert!(plot_arrowhead(c, c, 5, true).is_empty());
+    }
+
+    #[test]
+    fn test_arrowhead_open_is_outline() {
+        let tip = Coordinate::new(20, 20);
+        let back = Coordinate::new(20, 0);
+        let open = plot_arrowhead(tip, back, 8, false);
+        let filled = plot_arrowhead(tip, back, 8, true);
+        assert!(open.iter().all(|c| filled.contains(c)));
+        // The center of the base is only covered when filled.
+        assert!(filled.contains(&Coordinate::new(20, 14)));
+        assert!(!open.contains(&Coordinate::new(20, 14)));
+    }
+
     #[test]
     fn test_border() {
         assert_eq!(border(0, 0), 0);
@@ -411,7 +851,91 @@ mod tests {
         let c2 = Coordinate::new(0, 10);
         let plot = plot(c1, c2);
         for i in 0..10 {
-            assert_eq!(plot[i].y, i as i16);
+            assert_eq!(plot[i].y, i as i32);
         }
     }
+
+    fn is_connected(plot: &[Coordinate]) -> bool {
+        plot.windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() <= 1 && (w[1].y - w[0].y).abs() <= 1)
+    }
+
+    #[test]
+    fn test_curves_are_connected() {
+        let (a, b) = (Coordinate::new(0, 0), Coordinate::new(40, 10));
+        assert!(is_connected(&plot_quadratic(a, coordinate!(20, 40), b)));
+        assert!(is_connected(&plot_cubic(
+            a,
+            coordinate!(10, -30),
+            coordinate!(30, 30),
+            b
+        )));
+        assert!(is_connected(&plot_catmull_rom(&[
+            a,
+            coordinate!(20, 20),
+            b
+        ])));
+        assert!(is_connected(&plot_bundled(a, coordinate!(200, 90), 90)));
+    }
+
+    #[test]
+    fn test_cubic_ends() {
+        let (a, b) = (Coordinate::new(3, 4), Coordinate::new(-20, 9));
+        let plot = plot_cubic(a, coordinate!(0, 50), coordinate!(-10, -50), b);
+        assert_eq!(plot.first(), Some(&a));
+        assert_eq!(plot.last(), Some(&b));
+    }
+
+    #[test]
+    fn test_orthogonal_is_axis_aligned() {
+        let wall = Rect::new(coordinate!(10, -5), coordinate!(12, 5));
+        let plot = plot_orthogonal(coordinate!(0, 0), coordinate!(20, 3), &[wall]);
+        assert!(is_connected(&plot));
+        assert!(plot
+            .windows(2)
+            .all(|w| w[0].x == w[1].x || w[0].y == w[1].y));
+        assert!(plot.iter().all(|c| !wall.contains(c)));
+    }
+
+    #[test]
+    fn test_orthogonal_ignores_obstacles_at_ends() {
+        let around = Rect::new(coordinate!(-5, -5), coordinate!(5, 5));
+        let plot = plot_orthogonal(coordinate!(0, 0), coordinate!(20, 0), &[around]);
+        assert_eq!(plot.len(), 21);
+    }
+
+    #[test]
+    fn test_orthogonal_detours_around_obstacles_outside_of_ends() {
+        let obstacles = [
+            Rect::new(coordinate!(40, -10), coordinate!(60, 10)),
+            Rect::new(coordinate!(30, 11), coordinate!(70, 30)),
+            Rect::new(coordinate!(30, -30), coordinate!(70, -11)),
+        ];
+        let plot = plot_orthogonal(coordinate!(0, 0), coordinate!(100, 0), &obstacles);
+        assert!(is_connected(&plot));
+        assert_eq!(plot.last(), Some(&coordinate!(100, 0)));
+        assert!(plot
+            .iter()
+            .all(|c| obstacles.iter().all(|r| !r.contains(c))));
+    }
+
+    #[test]
+    fn test_orthogonal_ignores_distant_obstacles() {
+        let wall = Rect::new(coordinate!(10, -5), coordinate!(12, 5));
+        let mut obstacles = (0..40)
+            .map(|i| {
+                let c = coordinate!(500 + i * 20, 500);
+                Rect::new(c, c + coordinate!(5, 5))
+            })
+            .collect::<Vec<_>>();
+        obstacles.push(wall);
+        let plot = plot_orthogonal(coordinate!(0, 0), coordinate!(20, 0), &obstacles);
+        assert!(plot.iter().all(|c| !wall.contains(c)));
+    }
+
+    #[test]
+    fn test_bundled_straight_without_strength() {
+        let (a, b) = (Coordinate::new(0, 0), Coordinate::new(100, 0));
+        assert!(plot_bundled(a, b, 0).iter().all(|c| c.y == 0));
+    }
 }
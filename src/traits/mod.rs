@@ -6,11 +6,88 @@ use super::*;
 
 /**
 Functions required to draw the structure on the image.
+
+Implementations written for the earlier signature, which took and returned the image by value,
+can implement DrawOwned instead, which implements Draw for them.
  */
 pub trait Draw {
-    fn draw(&self, image: IW, offset: Coordinate, shape: &Shape) -> IW;
+    fn draw(&self, canvas: &mut IW, offset: Coordinate, shape: &Shape);
     fn size(&self) -> u32;
     fn links(&self) -> &[HL];
+
+    /**
+    Returns the area the structure draws on when drawn with the shape, before the offset is added.
+
+    None if the area is not known, in which case the structure may draw anywhere.
+     */
+    fn bounds(&self, _shape: &Shape) -> Option<Rect> {
+        None
+    }
+}
+
+/**
+Draw with the earlier signature, which took and returned the image by value.
+
+Every DrawOwned is also Draw, so only the name of the draw function has to change when migrating.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+struct Marker(Coordinate);
+
+impl DrawOwned for Marker {
+    fn draw_owned(&self, mut image: IW, offset: Coordinate, _shape: &Shape) -> IW {
+        image.put(&(self.0 + offset), image::Rgba([255, 0, 0, 255]));
+        image
+    }
+
+    fn size(&self) -> u32 {
+        1
+    }
+
+    fn links(&self) -> &[HL] {
+        &[]
+    }
+}
+
+let mut canvas = IW::from(image::RgbaImage::new(4, 4));
+Marker(Coordinate::new(1, 2)).draw(&mut canvas, Coordinate::new(1, 0), &Shape::Square);
+assert_eq!(canvas.image().get_pixel(2, 2), &image::Rgba([255, 0, 0, 255]));
+```
+ */
+pub trait DrawOwned {
+    fn draw_owned(&self, image: IW, offset: Coordinate, shape: &Shape) -> IW;
+    fn size(&self) -> u32;
+    fn links(&self) -> &[HL];
+
+    /**
+    Returns the area the structure draws on when drawn with the shape, before the offset is added.
+
+    None if the area is not known, in which case the structure may draw anywhere.
+     */
+    fn bounds(&self, _shape: &Shape) -> Option<Rect> {
+        None
+    }
+}
+
+impl<T: DrawOwned> Draw for T {
+    fn draw(&self, canvas: &mut IW, offset: Coordinate, shape: &Shape) {
+        canvas.apply(&|image| self.draw_owned(image, offset, shape));
+    }
+
+    fn size(&self) -> u32 {
+        DrawOwned::size(self)
+    }
+
+    fn links(&self) -> &[HL] {
+        DrawOwned::links(self)
+    }
+
+    fn bounds(&self, shape: &Shape) -> Option<Rect> {
+        DrawOwned::bounds(self, shape)
+    }
 }
 
 /**
@@ -32,30 +109,59 @@ pub trait Location {
     /**
     Retrieves the X coordinate.
      */
-    fn x(&self) -> i16 {
+    fn x(&self) -> i32 {
         self.position().x
     }
 
     /**
     Retrieves the Y coordinate.
      */
-    fn y(&self) -> i16 {
+    fn y(&self) -> i32 {
         self.position().y
     }
 
     /**
     Returns the sum of the x and y value.
      */
-    fn sum(&self) -> i16 {
+    fn sum(&self) -> i32 {
         self.x() + self.y()
     }
+
+    /**
+    Returns the positions of everything the structure draws, such as the Nodes inside a Group.
+     */
+    fn positions(&self) -> Vec<Coordinate> {
+        vec![self.position()]
+    }
+}
+
+/**
+Receives the frames of an animation, such as a Gif.
+ */
+pub trait Sink {
+    /**
+    Pushes a frame using a Map, shown for the default delay of the Sink.
+     */
+    fn push(&mut self, map: Map) -> Result<(), std::io::Error>;
+
+    /**
+    Pushes a frame shown for the given delay in units of 10 ms.
+     */
+    fn push_frame_delay(&mut self, image: &IW, delay: u16) -> Result<(), std::io::Error>;
+
+    /**
+    Writes anything the Sink has buffered, no more frames should be pushed afterwards.
+     */
+    fn finish(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
 }
 
 /**
 Enables retrieving the minimum and maximum position for the structure.
  */
 pub trait MinMax {
-    fn min_max(&self) -> (Coordinate, Coordinate);
+    fn min_max(&self) -> Rect;
 }
 
 /**